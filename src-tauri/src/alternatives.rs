@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::glossary::GlossaryViolation;
+use crate::prompts::RenderedPrompt;
+
+pub const MAX_ALTERNATIVES: u32 = 5;
+
+/// Each further alternative is sampled this much hotter than the previous one.
+const TEMPERATURE_STEP: f32 = 0.3;
+const MAX_TEMPERATURE: f32 = 1.2;
+
+/// Several candidate translations for short texts such as UI strings, offered side by side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlternativesConfig {
+    /// Number of candidates requested (duplicates are dropped, so fewer may be shown)
+    pub count: u32,
+    /// Clipboard texts up to this many characters get alternatives from `Cmd+T`; 0 disables this
+    pub max_chars: u32,
+}
+
+impl Default for AlternativesConfig {
+    fn default() -> Self {
+        AlternativesConfig {
+            count: 3,
+            max_chars: 80,
+        }
+    }
+}
+
+impl AlternativesConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(2..=MAX_ALTERNATIVES).contains(&self.count) {
+            return Err(format!("alternatives.count must be between 2 and {}", MAX_ALTERNATIVES));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alternative {
+    /// Position in the request order; chunks streamed for this candidate carry the same index
+    pub index: usize,
+    pub text: String,
+    pub temperature: f32,
+    pub glossary_violations: Vec<GlossaryViolation>,
+}
+
+/// Streamed piece of one candidate.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlternativeChunk<'a> {
+    pub index: usize,
+    pub chunk: &'a str,
+}
+
+/// Sampling temperature for each candidate, starting at the configured one.
+pub fn temperatures(base: f32, count: u32) -> Vec<f32> {
+    let ceiling = base.max(MAX_TEMPERATURE);
+    (0..count).map(|index| (base + index as f32 * TEMPERATURE_STEP).min(ceiling)).collect()
+}
+
+/// Candidates differing only in case, spacing, quotes or final punctuation count as the same.
+fn dedup_key(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '“' | '”' | '«' | '»' | '„'))
+        .trim_end_matches(['.', '!', '。', '！'])
+        .to_lowercase()
+}
+
+/// Requests `count` translations concurrently, one call per temperature, streaming every delta to
+/// `on_chunk` with its candidate index. Returns the distinct successful candidates in index order.
+pub async fn generate<F>(api_key: &str, model: &str, base_temperature: f32, prompt: &RenderedPrompt, count: u32, on_chunk: F) -> Result<Vec<Alternative>, String>
+where
+    F: Fn(usize, &str) + Clone + Send + Sync + 'static,
+{
+    let mut calls = tokio::task::JoinSet::new();
+    for (index, temperature) in temperatures(base_temperature, count).into_iter().enumerate() {
+        let api_key = api_key.to_string();
+        let payload = serde_json::json!({
+            "model": model,
+            "messages": [
+                { "role": "system", "content": prompt.system },
+                { "role": "user", "content": prompt.user }
+            ],
+            "temperature": temperature,
+            "max_tokens": 1000,
+            "stream": true
+        });
+        let on_chunk = on_chunk.clone();
+        calls.spawn(async move {
+            let result = crate::stream_chat_completion(&api_key, payload, |chunk| on_chunk(index, chunk)).await;
+            (index, temperature, result)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = calls.join_next().await {
+        results.push(joined.map_err(|e| format!("Alternative translation task failed: {}", e))?);
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+
+    let mut last_error = None;
+    let mut seen = Vec::new();
+    let mut alternatives = Vec::new();
+    for (index, temperature, result) in results {
+        match result {
+            Ok(text) => {
+                let text = text.trim().to_string();
+                let key = dedup_key(&text);
+                if text.is_empty() || seen.contains(&key) {
+                    continue;
+                }
+                seen.push(key);
+                alternatives.push(Alternative { index, text, temperature, glossary_violations: Vec::new() });
+            }
+            Err(e) => {
+                println!("⚠️ Alternative {} failed: {}", index + 1, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    if alternatives.is_empty() {
+        return Err(last_error.unwrap_or_else(|| "No translation content in response".to_string()));
+    }
+    println!("🔀 {} distinct alternative(s) out of {}", alternatives.len(), count);
+    Ok(alternatives)
+}
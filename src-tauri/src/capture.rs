@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::diarization::AudioSource;
+use crate::CaptureFormat;
+
+/// Payload of the `audio-device-changed` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub input_devices: Vec<String>,
+}
+
+/// Payload of the `recording-interrupted` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingInterrupted {
+    pub source: AudioSource,
+    pub device_name: Option<String>,
+    pub error: String,
+    pub will_recover: bool,
+}
+
+/// Payload of the `recording-recovered` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingRecovered {
+    pub source: AudioSource,
+    pub device_name: Option<String>,
+    pub gap_seconds: f32,
+}
+
+/// Compares two snapshots of the input device list.
+pub fn diff_devices(previous: &[String], current: &[String]) -> Option<DeviceChange> {
+    let added: Vec<String> = current.iter().filter(|name| !previous.contains(name)).cloned().collect();
+    let removed: Vec<String> = previous.iter().filter(|name| !current.contains(name)).cloned().collect();
+
+    if added.is_empty() && removed.is_empty() {
+        return None;
+    }
+
+    Some(DeviceChange {
+        added,
+        removed,
+        input_devices: current.to_vec(),
+    })
+}
+
+/// Converts interleaved samples from a replacement device into the format the session started with,
+/// so a failover never mixes sample rates or channel layouts in one buffer.
+#[derive(Debug, Clone)]
+pub struct SampleConverter {
+    from: CaptureFormat,
+    to: CaptureFormat,
+    position: f64,
+    previous_frame: Option<Vec<f32>>,
+}
+
+impl SampleConverter {
+    pub fn new(from: CaptureFormat, to: CaptureFormat) -> Self {
+        SampleConverter {
+            from,
+            to,
+            position: 0.0,
+            previous_frame: None,
+        }
+    }
+
+    fn map_channels(&self, frame: &[f32]) -> Vec<f32> {
+        let target = self.to.channels.max(1) as usize;
+        if frame.len() == target {
+            return frame.to_vec();
+        }
+        if target == 1 {
+            return vec![frame.iter().sum::<f32>() / frame.len() as f32];
+        }
+        (0..target).map(|channel| frame[channel.min(frame.len() - 1)]).collect()
+    }
+
+    pub fn process(&mut self, data: &[f32]) -> Vec<f32> {
+        if self.from == self.to {
+            return data.to_vec();
+        }
+
+        let mut frames: Vec<Vec<f32>> = Vec::with_capacity(data.len() / self.from.channels.max(1) as usize + 1);
+        if let Some(previous) = self.previous_frame.take() {
+            frames.push(previous);
+        }
+        frames.extend(data.chunks(self.from.channels.max(1) as usize).map(|frame| self.map_channels(frame)));
+
+        if self.from.sample_rate == self.to.sample_rate {
+            return frames.into_iter().flatten().collect();
+        }
+
+        // Linear interpolation; the last frame is carried over so blocks join seamlessly
+        let step = self.from.sample_rate as f64 / self.to.sample_rate as f64;
+        let mut output = Vec::new();
+        while self.position + 1.0 < frames.len() as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            for (current, next) in frames[index].iter().zip(&frames[index + 1]) {
+                output.push(current + (next - current) * fraction);
+            }
+            self.position += step;
+        }
+
+        if let Some(last) = frames.pop() {
+            self.position -= frames.len() as f64;
+            self.previous_frame = Some(last);
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(sample_rate: u32, channels: u16) -> CaptureFormat {
+        CaptureFormat { sample_rate, channels }
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{:?} vs {:?}", actual, expected);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{:?} vs {:?}", actual, expected);
+        }
+    }
+
+    /// Mono ramp 0, 1, 2, ... so linear interpolation has exact expected values.
+    fn ramp(frames: usize) -> Vec<f32> {
+        (0..frames).map(|i| i as f32).collect()
+    }
+
+    #[test]
+    fn reports_added_and_removed_devices() {
+        let previous = vec!["MacBook Microphone".to_string(), "USB Headset".to_string()];
+        let current = vec!["MacBook Microphone".to_string(), "BlackHole 2ch".to_string()];
+        let change = diff_devices(&previous, &current).unwrap();
+        assert_eq!(change.added, vec!["BlackHole 2ch"]);
+        assert_eq!(change.removed, vec!["USB Headset"]);
+        assert_eq!(change.input_devices, current);
+        assert!(diff_devices(&current, &current).is_none());
+    }
+
+    #[test]
+    fn same_format_passes_through() {
+        let mut converter = SampleConverter::new(format(48_000, 2), format(48_000, 2));
+        assert_eq!(converter.process(&[0.1, 0.2, 0.3, 0.4]), vec![0.1, 0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn downmixes_stereo_to_mono_by_averaging() {
+        let mut converter = SampleConverter::new(format(48_000, 2), format(48_000, 1));
+        assert_close(&converter.process(&[1.0, 0.0, 0.5, 0.5, -1.0, 1.0]), &[0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn copies_mono_to_both_stereo_channels() {
+        let mut converter = SampleConverter::new(format(48_000, 1), format(48_000, 2));
+        assert_close(&converter.process(&[0.25, -0.5]), &[0.25, 0.25, -0.5, -0.5]);
+    }
+
+    #[test]
+    fn downsampling_keeps_every_third_frame_of_a_ramp() {
+        let mut converter = SampleConverter::new(format(48_000, 1), format(16_000, 1));
+        // The last frame is held back until the next block arrives
+        assert_close(&converter.process(&ramp(10)), &[0.0, 3.0, 6.0]);
+    }
+
+    #[test]
+    fn upsampling_interpolates_between_frames() {
+        let mut converter = SampleConverter::new(format(16_000, 1), format(48_000, 1));
+        let expected: Vec<f32> = (0..9).map(|i| i as f32 / 3.0).collect();
+        assert_close(&converter.process(&ramp(4)), &expected);
+    }
+
+    #[test]
+    fn resampling_in_blocks_matches_one_pass() {
+        let input = ramp(4_800);
+        let mut whole = SampleConverter::new(format(44_100, 1), format(48_000, 1));
+        let expected = whole.process(&input);
+
+        let mut blocks = SampleConverter::new(format(44_100, 1), format(48_000, 1));
+        let output: Vec<f32> = input.chunks(441).flat_map(|block| blocks.process(block)).collect();
+        assert_close(&output, &expected);
+
+        // 4800 frames at 44.1 kHz last 108.8 ms, which is about 5224 frames at 48 kHz
+        assert!((5_220..=5_225).contains(&output.len()), "got {} frames", output.len());
+    }
+
+    #[test]
+    fn resamples_and_downmixes_together() {
+        let mut converter = SampleConverter::new(format(48_000, 2), format(16_000, 1));
+        let stereo: Vec<f32> = ramp(7).iter().flat_map(|&s| [s + 1.0, s - 1.0]).collect();
+        assert_close(&converter.process(&stereo), &[0.0, 3.0]);
+    }
+}
@@ -0,0 +1,499 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::settings;
+
+const KEYRING_SERVICE: &str = "shorty.ink";
+const KEYRING_USER: &str = "openai-api-key";
+const ENV_VAR: &str = "OPENAI_API_KEY";
+
+/// The credential used when nothing else is selected; `.env` keys are imported under this name.
+pub const DEFAULT_CREDENTIAL: &str = "personal";
+// Names of stored credentials (not the keys), since keyrings cannot be enumerated
+const INDEX_FILE: &str = "credentials.json";
+
+// Fallback when no keyring daemon is available (e.g. a headless Linux session). The AES key sits
+// next to the ciphertext, so this only keeps keys out of plain sight; it is not protection at rest.
+const ENCRYPTED_FILE: &str = "credentials.enc";
+const ENCRYPTION_KEY_FILE: &str = "credentials.key";
+// Present once the default key was cleared, so the `.env` key is not imported again
+const ENV_CLEARED_FILE: &str = "credentials.env-cleared";
+const NONCE_LEN: usize = 12;
+
+/// Where the API key was found.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStorage {
+    Keyring,
+    /// Encrypted with a key stored alongside it: obfuscated, not secure
+    ObfuscatedFile,
+    Environment,
+}
+
+/// A resolved API key and where it came from. Never serialized, so it cannot reach a window.
+#[derive(Debug, Clone)]
+pub struct Credential {
+    pub name: String,
+    pub api_key: String,
+    pub storage: KeyStorage,
+}
+
+/// What the frontend may know about a named key: never the key itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyStatus {
+    pub name: String,
+    pub active: bool,
+    pub configured: bool,
+    pub storage: Option<KeyStorage>,
+    /// Last four characters, so users can tell keys apart
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyTestResult {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Credential names end up in keyring entries and file keys, so keep them simple.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid credential name '{}': use 1-32 lowercase letters, digits, '-' or '_'", name))
+    }
+}
+
+fn keyring_entry(name: &str) -> Result<keyring::Entry, String> {
+    // The default credential keeps the entry name it had before keys were named
+    let user = if name == DEFAULT_CREDENTIAL {
+        KEYRING_USER.to_string()
+    } else {
+        format!("{}:{}", KEYRING_USER, name)
+    };
+    keyring::Entry::new(KEYRING_SERVICE, &user)
+        .map_err(|e| format!("Failed to open keyring: {}", e))
+}
+
+fn credentials_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Writes a file readable only by the current user.
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), String> {
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict permissions on {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+fn load_index(dir: &Path) -> Vec<String> {
+    std::fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(dir: &Path, names: &[String]) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(names)
+        .map_err(|e| format!("Failed to serialize credentials index: {}", e))?;
+    write_private(&dir.join(INDEX_FILE), contents.as_bytes())
+}
+
+/// Loads the file encryption key, creating a random one on first use.
+fn encryption_key(dir: &Path) -> Result<Aes256Gcm, String> {
+    let path = dir.join(ENCRYPTION_KEY_FILE);
+    let key = match std::fs::read(&path) {
+        Ok(key) if key.len() == 32 => key,
+        Ok(_) => return Err("Credentials key file is corrupt".to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut key = vec![0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            write_private(&path, &key)?;
+            key
+        }
+        Err(e) => return Err(format!("Failed to read credentials key: {}", e)),
+    };
+    Aes256Gcm::new_from_slice(&key).map_err(|e| format!("Invalid credentials key: {}", e))
+}
+
+/// Reads the name-to-key map from the encrypted fallback file.
+fn read_encrypted_file(dir: &Path) -> Result<HashMap<String, String>, String> {
+    let data = match std::fs::read(dir.join(ENCRYPTED_FILE)) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(e) => return Err(format!("Failed to read encrypted credentials: {}", e)),
+    };
+    if data.len() <= NONCE_LEN {
+        return Err("Encrypted credentials file is corrupt".to_string());
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = encryption_key(dir)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt credentials")?;
+    match serde_json::from_slice(&plaintext) {
+        Ok(keys) => Ok(keys),
+        // Files written before keys were named hold a single bare key
+        Err(_) => String::from_utf8(plaintext)
+            .map(|key| HashMap::from([(DEFAULT_CREDENTIAL.to_string(), key)]))
+            .map_err(|_| "Encrypted credentials are not valid UTF-8".to_string()),
+    }
+}
+
+fn write_encrypted_file(dir: &Path, keys: &HashMap<String, String>) -> Result<(), String> {
+    if keys.is_empty() {
+        let _ = std::fs::remove_file(dir.join(ENCRYPTED_FILE));
+        let _ = std::fs::remove_file(dir.join(ENCRYPTION_KEY_FILE));
+        return Ok(());
+    }
+
+    let plaintext = serde_json::to_vec(keys)
+        .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = encryption_key(dir)?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| "Failed to encrypt credentials")?;
+
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    write_private(&dir.join(ENCRYPTED_FILE), &data)
+}
+
+/// Looks a named key up in the keyring, then the encrypted file, then (for the default) the environment.
+fn lookup(app_handle: &tauri::AppHandle, name: &str) -> Option<Credential> {
+    match keyring_entry(name).map(|entry| entry.get_password()) {
+        Ok(Ok(api_key)) => {
+            return Some(Credential { name: name.to_string(), api_key, storage: KeyStorage::Keyring });
+        }
+        Ok(Err(keyring::Error::NoEntry)) => {}
+        Ok(Err(e)) => println!("⚠️ Keyring unavailable: {}", e),
+        Err(e) => println!("⚠️ {}", e),
+    }
+
+    match credentials_dir(app_handle) {
+        Ok(dir) => lookup_fallback(&dir, name),
+        Err(e) => {
+            println!("⚠️ {}", e);
+            None
+        }
+    }
+}
+
+/// The keyring-less part of `lookup`: the obfuscated file, then the environment for the default
+/// credential unless the user cleared it.
+fn lookup_fallback(dir: &Path, name: &str) -> Option<Credential> {
+    let found = |api_key: String, storage: KeyStorage| Credential {
+        name: name.to_string(),
+        api_key,
+        storage,
+    };
+
+    match read_encrypted_file(dir) {
+        Ok(mut keys) => {
+            if let Some(key) = keys.remove(name) {
+                return Some(found(key, KeyStorage::ObfuscatedFile));
+            }
+        }
+        Err(e) => println!("⚠️ {}", e),
+    }
+
+    if name != DEFAULT_CREDENTIAL || dir.join(ENV_CLEARED_FILE).exists() {
+        return None;
+    }
+    std::env::var(ENV_VAR).ok()
+        .filter(|key| !key.trim().is_empty())
+        .map(|key| found(key, KeyStorage::Environment))
+}
+
+/// Resolves the named credential, or the active one from settings. Keys never leave the backend.
+pub fn resolve(app_handle: &tauri::AppHandle, name: Option<&str>) -> Result<Credential, String> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => settings::current(app_handle).active_credential,
+    };
+    lookup(app_handle, &name)
+        .ok_or_else(|| format!("No OpenAI API key configured for '{}'. Add one in the app or set OPENAI_API_KEY.", name))
+}
+
+/// The active credential's key, for requests that don't report which credential they used.
+pub fn api_key(app_handle: &tauri::AppHandle) -> Result<String, String> {
+    resolve(app_handle, None).map(|credential| credential.api_key)
+}
+
+/// Saves the key to the keyring, or to the encrypted file if no keyring is available.
+fn store(app_handle: &tauri::AppHandle, name: &str, api_key: &str) -> Result<KeyStorage, String> {
+    let dir = credentials_dir(app_handle)?;
+    let mut file_keys = read_encrypted_file(&dir).unwrap_or_default();
+
+    let storage = match keyring_entry(name).and_then(|entry| entry.set_password(api_key).map_err(|e| e.to_string())) {
+        Ok(()) => {
+            // Don't leave an older copy behind in the fallback file
+            if file_keys.remove(name).is_some() {
+                write_encrypted_file(&dir, &file_keys)?;
+            }
+            KeyStorage::Keyring
+        }
+        Err(e) => {
+            println!("⚠️ Keyring unavailable ({}), using an obfuscated file instead - the key is not protected at rest", e);
+            file_keys.insert(name.to_string(), api_key.to_string());
+            write_encrypted_file(&dir, &file_keys)?;
+            KeyStorage::ObfuscatedFile
+        }
+    };
+    record_stored(&dir, name)?;
+    Ok(storage)
+}
+
+/// Adds a stored key to the index; storing the default key again also lets `.env` count again.
+fn record_stored(dir: &Path, name: &str) -> Result<(), String> {
+    let mut names = load_index(dir);
+    if !names.iter().any(|existing| existing == name) {
+        names.push(name.to_string());
+        save_index(dir, &names)?;
+    }
+    if name == DEFAULT_CREDENTIAL {
+        let _ = std::fs::remove_file(dir.join(ENV_CLEARED_FILE));
+    }
+    Ok(())
+}
+
+/// Removes a key from the obfuscated file and the index. Clearing the default key also disables
+/// `OPENAI_API_KEY`, so a key the user removed is neither used nor imported again.
+fn remove_stored(dir: &Path, name: &str) -> Result<(), String> {
+    let mut file_keys = read_encrypted_file(dir).unwrap_or_default();
+    if file_keys.remove(name).is_some() {
+        write_encrypted_file(dir, &file_keys)?;
+    }
+    let names: Vec<String> = load_index(dir).into_iter().filter(|existing| existing != name).collect();
+    save_index(dir, &names)?;
+    if name == DEFAULT_CREDENTIAL {
+        write_private(&dir.join(ENV_CLEARED_FILE), b"")?;
+    }
+    Ok(())
+}
+
+/// Moves a key from `.env` into secure storage the first time the app sees it, unless the
+/// default key was cleared since.
+pub fn import_env_key(app_handle: &tauri::AppHandle) {
+    dotenvy::dotenv().ok();
+    let Ok(env_key) = std::env::var(ENV_VAR) else {
+        return;
+    };
+    if credentials_dir(app_handle).is_ok_and(|dir| dir.join(ENV_CLEARED_FILE).exists()) {
+        return;
+    }
+
+    match lookup(app_handle, DEFAULT_CREDENTIAL) {
+        Some(Credential { storage: KeyStorage::Keyring | KeyStorage::ObfuscatedFile, .. }) => {}
+        _ => match store(app_handle, DEFAULT_CREDENTIAL, env_key.trim()) {
+            Ok(storage) => println!("🔐 Imported {} into {:?} storage - it can now be removed from .env", ENV_VAR, storage),
+            Err(e) => println!("⚠️ Failed to import {} into secure storage: {}", ENV_VAR, e),
+        },
+    }
+}
+
+fn status(app_handle: &tauri::AppHandle, name: &str, active: &str) -> ApiKeyStatus {
+    let credential = lookup(app_handle, name);
+    ApiKeyStatus {
+        name: name.to_string(),
+        active: name == active,
+        configured: credential.is_some(),
+        storage: credential.as_ref().map(|credential| credential.storage),
+        hint: credential.map(|credential| {
+            let chars: Vec<char> = credential.api_key.chars().collect();
+            chars[chars.len().saturating_sub(4)..].iter().collect()
+        }),
+    }
+}
+
+/// Status of every known credential: the default, anything stored, and the active one.
+fn list(app_handle: &tauri::AppHandle) -> Result<Vec<ApiKeyStatus>, String> {
+    let active = settings::current(app_handle).active_credential;
+    let mut names = vec![DEFAULT_CREDENTIAL.to_string()];
+    for name in load_index(&credentials_dir(app_handle)?).into_iter().chain(std::iter::once(active.clone())) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Ok(names.iter().map(|name| status(app_handle, name, &active)).collect())
+}
+
+#[tauri::command]
+pub async fn list_credentials(app_handle: tauri::AppHandle) -> Result<Vec<ApiKeyStatus>, String> {
+    list(&app_handle)
+}
+
+#[tauri::command]
+pub async fn set_api_key(app_handle: tauri::AppHandle, name: Option<String>, api_key: String) -> Result<Vec<ApiKeyStatus>, String> {
+    let name = name.unwrap_or_else(|| DEFAULT_CREDENTIAL.to_string());
+    validate_name(&name)?;
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err("API key must not be empty".to_string());
+    }
+
+    let storage = store(&app_handle, &name, api_key)?;
+    println!("🔐 OpenAI API key '{}' saved ({:?})", name, storage);
+    list(&app_handle)
+}
+
+#[tauri::command]
+pub async fn clear_api_key(app_handle: tauri::AppHandle, name: Option<String>) -> Result<Vec<ApiKeyStatus>, String> {
+    let name = name.unwrap_or_else(|| DEFAULT_CREDENTIAL.to_string());
+    validate_name(&name)?;
+    if let Ok(entry) = keyring_entry(&name) {
+        let _ = entry.delete_credential();
+    }
+
+    remove_stored(&credentials_dir(&app_handle)?, &name)?;
+
+    println!("🔐 OpenAI API key '{}' removed", name);
+    list(&app_handle)
+}
+
+/// Checks a stored key against the OpenAI API without exposing it.
+#[tauri::command]
+pub async fn test_api_key(app_handle: tauri::AppHandle, name: Option<String>) -> Result<ApiKeyTestResult, String> {
+    let credential = match resolve(&app_handle, name.as_deref()) {
+        Ok(credential) => credential,
+        Err(e) => return Ok(ApiKeyTestResult { success: false, error: Some(e) }),
+    };
+
+    let response = reqwest::Client::new()
+        .get("https://api.openai.com/v1/models")
+        .header("Authorization", format!("Bearer {}", credential.api_key))
+        .send()
+        .await;
+
+    Ok(match response {
+        Ok(response) if response.status().is_success() => ApiKeyTestResult { success: true, error: None },
+        Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => ApiKeyTestResult {
+            success: false,
+            error: Some("The API key was rejected".to_string()),
+        },
+        Ok(response) => ApiKeyTestResult {
+            success: false,
+            error: Some(format!("HTTP error: {}", response.status())),
+        },
+        Err(e) => ApiKeyTestResult {
+            success: false,
+            error: Some(format!("Request failed: {}", e)),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory under the system temp dir, unique to the test.
+    fn empty_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("shorty-credentials-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// What `store` does when no keyring is available.
+    fn store_in_file(dir: &Path, name: &str, api_key: &str) {
+        let mut keys = read_encrypted_file(dir).unwrap();
+        keys.insert(name.to_string(), api_key.to_string());
+        write_encrypted_file(dir, &keys).unwrap();
+        record_stored(dir, name).unwrap();
+    }
+
+    #[test]
+    fn names_are_validated() {
+        for name in ["personal", "team-2", "client_a"] {
+            assert!(validate_name(name).is_ok(), "{}", name);
+        }
+        for name in ["", "Team", "a b", "../keys", &"x".repeat(33)] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn file_backend_stores_looks_up_and_clears_named_keys() {
+        let dir = empty_dir("file");
+        store_in_file(&dir, "team", "sk-team-1234");
+        store_in_file(&dir, "client", "sk-client-5678");
+
+        let credential = lookup_fallback(&dir, "team").unwrap();
+        assert_eq!(credential.name, "team");
+        assert_eq!(credential.api_key, "sk-team-1234");
+        assert_eq!(credential.storage, KeyStorage::ObfuscatedFile);
+        assert_eq!(load_index(&dir), vec!["team".to_string(), "client".to_string()]);
+
+        // The key is not readable from the file without the key file
+        let raw = std::fs::read(dir.join(ENCRYPTED_FILE)).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("sk-team-1234"));
+
+        remove_stored(&dir, "team").unwrap();
+        assert!(lookup_fallback(&dir, "team").is_none());
+        assert_eq!(lookup_fallback(&dir, "client").unwrap().api_key, "sk-client-5678");
+        assert_eq!(load_index(&dir), vec!["client".to_string()]);
+
+        // Clearing the last key removes the files altogether
+        remove_stored(&dir, "client").unwrap();
+        assert!(!dir.join(ENCRYPTED_FILE).exists());
+        assert!(!dir.join(ENCRYPTION_KEY_FILE).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn files_written_before_keys_were_named_hold_the_default_key() {
+        let dir = empty_dir("legacy");
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = encryption_key(&dir).unwrap().encrypt(Nonce::from_slice(&nonce), b"sk-legacy".as_slice()).unwrap();
+        std::fs::write(dir.join(ENCRYPTED_FILE), [nonce.as_slice(), &ciphertext].concat()).unwrap();
+
+        assert_eq!(lookup_fallback(&dir, DEFAULT_CREDENTIAL).unwrap().api_key, "sk-legacy");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clearing_the_default_key_disables_the_environment_key() {
+        let dir = empty_dir("environment");
+        std::env::set_var(ENV_VAR, "sk-from-env");
+
+        let credential = lookup_fallback(&dir, DEFAULT_CREDENTIAL).unwrap();
+        assert_eq!(credential.storage, KeyStorage::Environment);
+        assert_eq!(credential.api_key, "sk-from-env");
+        // Only the default credential falls back to the environment
+        assert!(lookup_fallback(&dir, "team").is_none());
+
+        store_in_file(&dir, DEFAULT_CREDENTIAL, "sk-stored");
+        assert_eq!(lookup_fallback(&dir, DEFAULT_CREDENTIAL).unwrap().storage, KeyStorage::ObfuscatedFile);
+
+        remove_stored(&dir, DEFAULT_CREDENTIAL).unwrap();
+        assert!(lookup_fallback(&dir, DEFAULT_CREDENTIAL).is_none());
+
+        // Saving a default key again lifts the block
+        store_in_file(&dir, DEFAULT_CREDENTIAL, "sk-new");
+        assert!(!dir.join(ENV_CLEARED_FILE).exists());
+
+        std::env::remove_var(ENV_VAR);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::prompts::{self, PromptTemplate};
+use crate::{credentials, diarization, settings, ResponseMetadata};
+
+/// Directory (inside the app config directory) holding one `<id>.json` file per agent.
+const AGENTS_DIR: &str = "agents";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputSource {
+    Clipboard,
+    /// Text handed over by the caller (e.g. selected in an app window); the clipboard when none is given
+    Selection,
+    /// Transcript of the last recording
+    Audio,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputSink {
+    /// Streams into a window of its own
+    Window,
+    Clipboard,
+    /// A short message in the main window
+    Notification,
+}
+
+/// A user-defined agent, loaded from `agents/<id>.json` in the app config directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomAgent {
+    /// File name without `.json`; set when loading
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Global shortcut such as "cmd+shift+j"; agents without one are run from code
+    pub shortcut: Option<String>,
+    /// Same variables as the built-in templates: `{{text}}` is the input
+    pub prompt: PromptTemplate,
+    /// The settings' model and temperature when unset
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub input: InputSource,
+    pub output: OutputSink,
+}
+
+impl CustomAgent {
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_valid_id(&self.id) {
+            return Err(format!("invalid id '{}': use letters, digits, '-' and '_' in the file name", self.id));
+        }
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if self.shortcut.as_deref().is_some_and(|shortcut| shortcut.trim().is_empty()) {
+            return Err("shortcut must not be empty".to_string());
+        }
+        self.prompt.validate().map_err(|e| format!("prompt: {}", e))?;
+        if self.model.as_deref().is_some_and(|model| model.trim().is_empty()) {
+            return Err("model must not be empty".to_string());
+        }
+        if self.temperature.is_some_and(|temperature| !(0.0..=2.0).contains(&temperature)) {
+            return Err("temperature must be between 0 and 2".to_string());
+        }
+        if self.max_tokens == Some(0) {
+            return Err("max_tokens must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The valid agents plus a message for every file that could not be loaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomAgentList {
+    pub agents: Vec<CustomAgent>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomAgentRun {
+    pub agent: String,
+    pub output: String,
+    pub sink: OutputSink,
+    pub metadata: ResponseMetadata,
+}
+
+/// Payload of the `custom-agent-notification` event for the notification sink.
+#[derive(Debug, Clone, Serialize)]
+struct AgentNotification<'a> {
+    agent: &'a str,
+    name: &'a str,
+    message: &'a str,
+}
+
+/// Ids are file names, so they are limited to characters that can't leave the agents directory.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Parses the contents of `<id>.json` and validates the agent.
+fn parse_agent(contents: &str, id: &str) -> Result<CustomAgent, String> {
+    let mut agent = serde_json::from_str::<CustomAgent>(contents).map_err(|e| e.to_string())?;
+    agent.id = id.to_string();
+    agent.validate()?;
+    Ok(agent)
+}
+
+fn agents_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(app_handle.path().app_config_dir()
+        .map_err(|e| format!("Failed to resolve app config directory: {}", e))?
+        .join(AGENTS_DIR))
+}
+
+/// Reads every `*.json` file in the agents directory, sorted by id.
+pub fn load_all(app_handle: &tauri::AppHandle) -> Result<CustomAgentList, String> {
+    let dir = agents_dir(app_handle)?;
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(CustomAgentList { agents: Vec::new(), errors: Vec::new() });
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", dir.display(), e)),
+    };
+
+    let mut agents = Vec::new();
+    let mut errors = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let id = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        let loaded = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| parse_agent(&contents, id));
+        match loaded {
+            Ok(agent) => agents.push(agent),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    agents.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(CustomAgentList { agents, errors })
+}
+
+/// Loads the single agent `<id>.json`, without reading the rest of the directory.
+pub fn find(app_handle: &tauri::AppHandle, id: &str) -> Result<CustomAgent, String> {
+    if !is_valid_id(id) {
+        return Err(format!("Invalid custom agent id '{}'", id));
+    }
+    let dir = agents_dir(app_handle)?;
+    let path = dir.join(format!("{}.json", id));
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!("No custom agent '{}' in {}", id, dir.display()));
+        }
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    parse_agent(&contents, id).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+async fn read_input(app_handle: &tauri::AppHandle, agent: &CustomAgent, api_key: &str, input: Option<String>) -> Result<String, String> {
+    let text = match (agent.input, input) {
+        (InputSource::Selection, Some(input)) => input,
+        (InputSource::Clipboard | InputSource::Selection, _) => app_handle.clipboard().read_text()
+            .map_err(|e| format!("Failed to read clipboard: {}", e))?,
+        (InputSource::Audio, _) => {
+            let tracks = crate::last_recording()?;
+            let settings = settings::current(app_handle);
+            let segments = crate::transcribe_tracks(api_key, &tracks, None, &settings.audio_processing).await?;
+            diarization::format_speaker_transcript(&segments, &tracks.pauses)
+        }
+    };
+    if text.trim().is_empty() {
+        return Err(format!("No input for '{}'", agent.name));
+    }
+    Ok(text)
+}
+
+/// Runs an agent on `input`, handing every streamed delta to `on_chunk`, and returns its output.
+/// Output sinks are up to the caller.
+pub async fn execute<F: FnMut(&str)>(
+    app_handle: &tauri::AppHandle,
+    agent: &CustomAgent,
+    api_key: &str,
+    input: &str,
+    on_chunk: F,
+) -> Result<String, String> {
+    let settings = settings::current(app_handle);
+    let prompt = agent.prompt.render(&HashMap::from([
+        ("text".to_string(), input.to_string()),
+        ("target_language".to_string(), settings.target_language.clone()),
+        ("tone".to_string(), prompts::DEFAULT_TONE.to_string()),
+    ]));
+    let mut payload = serde_json::json!({
+        "model": agent.model.as_deref().unwrap_or(&settings.model),
+        "messages": [
+            {
+                "role": "system",
+                "content": prompt.system
+            },
+            {
+                "role": "user",
+                "content": prompt.user
+            }
+        ],
+        "temperature": agent.temperature.unwrap_or(settings.temperature),
+        "stream": true
+    });
+    if let Some(max_tokens) = agent.max_tokens {
+        payload["max_tokens"] = max_tokens.into();
+    }
+
+    println!("🤖 Running custom agent '{}' ({} chars of input)...", agent.name, input.len());
+    let output = crate::stream_chat_completion(api_key, payload, on_chunk).await?;
+    Ok(output.trim().to_string())
+}
+
+#[tauri::command]
+pub async fn list_custom_agents(app_handle: tauri::AppHandle) -> Result<CustomAgentList, String> {
+    load_all(&app_handle)
+}
+
+/// Runs the agent with this id and delivers the output to its sink. `input` is used by agents
+/// whose input source is the selection; the others read the clipboard or the last recording.
+#[tauri::command]
+pub async fn run_custom_agent(app_handle: tauri::AppHandle, id: String, input: Option<String>) -> Result<CustomAgentRun, String> {
+    let agent = find(&app_handle, &id)?;
+    let credential = credentials::resolve(&app_handle, None)?;
+    let metadata = ResponseMetadata {
+        credential: credential.name.clone(),
+        model: agent.model.clone().unwrap_or_else(|| settings::current(&app_handle).model),
+    };
+    let input = read_input(&app_handle, &agent, &credential.api_key, input).await?;
+
+    let output = match agent.output {
+        OutputSink::Window => {
+            // Labels must be unique, so a second run reuses the agent's open window
+            let label = format!("agent-{}", agent.id);
+            let window = match app_handle.get_webview_window(&label) {
+                Some(window) => {
+                    let _ = window.set_focus();
+                    window
+                }
+                None => {
+                    let window = tauri::WebviewWindowBuilder::new(
+                        &app_handle,
+                        label,
+                        tauri::WebviewUrl::App("custom-agent.html".into())
+                    )
+                    .title(&agent.name)
+                    .inner_size(700.0, 500.0)
+                    .center()
+                    .resizable(true)
+                    .build()
+                    .map_err(|e| e.to_string())?;
+
+                    // Wait for window to load before sending events
+                    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+                    window
+                }
+            };
+            let _ = window.emit("agent-info", &agent);
+            let _ = window.emit("agent-input", &input);
+            let _ = window.emit("agent-metadata", &metadata);
+
+            let on_chunk = |chunk: &str| {
+                let _ = window.emit("agent-chunk", chunk);
+            };
+            match execute(&app_handle, &agent, &credential.api_key, &input, on_chunk).await {
+                Ok(output) => {
+                    let _ = window.emit("agent-complete", &output);
+                    output
+                }
+                Err(e) => {
+                    let _ = window.emit("agent-error", &e);
+                    return Err(e);
+                }
+            }
+        }
+        OutputSink::Clipboard => {
+            let output = execute(&app_handle, &agent, &credential.api_key, &input, |_| {}).await?;
+            app_handle.clipboard().write_text(output.clone())
+                .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+            output
+        }
+        OutputSink::Notification => {
+            let output = execute(&app_handle, &agent, &credential.api_key, &input, |_| {}).await?;
+            let _ = app_handle.emit("custom-agent-notification", &AgentNotification {
+                agent: &agent.id,
+                name: &agent.name,
+                message: &output,
+            });
+            output
+        }
+    };
+
+    println!("🎉 Custom agent '{}' finished ({:?})", agent.name, agent.output);
+    Ok(CustomAgentRun {
+        agent: agent.id,
+        output,
+        sink: agent.output,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGENT: &str = r#"{
+        "name": "Bullet points",
+        "prompt": { "system": "Turn the text into bullet points.", "user": "{{text}}" },
+        "temperature": 0.3,
+        "max_tokens": 500,
+        "input": "clipboard",
+        "output": "window"
+    }"#;
+
+    fn agent() -> CustomAgent {
+        parse_agent(AGENT, "bullet-points").unwrap()
+    }
+
+    #[test]
+    fn parse_takes_the_id_from_the_file_name() {
+        let agent = agent();
+        assert_eq!(agent.id, "bullet-points");
+        assert_eq!(agent.name, "Bullet points");
+        assert_eq!(agent.model, None);
+        assert_eq!(agent.input, InputSource::Clipboard);
+        assert_eq!(agent.output, OutputSink::Window);
+    }
+
+    #[test]
+    fn ids_are_limited_to_file_name_characters() {
+        for id in ["bullet-points", "notes_2", "A1"] {
+            assert!(is_valid_id(id), "{}", id);
+            assert!(parse_agent(AGENT, id).is_ok(), "{}", id);
+        }
+        for id in ["", "bullet points", "../settings", "notes.v2", "agents/notes", "ünicode"] {
+            assert!(!is_valid_id(id), "{}", id);
+            let error = parse_agent(AGENT, id).unwrap_err();
+            assert!(error.starts_with("invalid id"), "{}: {}", id, error);
+        }
+    }
+
+    #[test]
+    fn temperature_must_be_in_range() {
+        for temperature in [0.0, 1.0, 2.0] {
+            let agent = CustomAgent { temperature: Some(temperature), ..agent() };
+            assert!(agent.validate().is_ok(), "{}", temperature);
+        }
+        for temperature in [-0.1, 2.1, f32::NAN] {
+            let agent = CustomAgent { temperature: Some(temperature), ..agent() };
+            assert_eq!(agent.validate().unwrap_err(), "temperature must be between 0 and 2", "{}", temperature);
+        }
+    }
+
+    #[test]
+    fn max_tokens_must_be_positive() {
+        assert_eq!(CustomAgent { max_tokens: Some(0), ..agent() }.validate().unwrap_err(), "max_tokens must be at least 1");
+        assert!(CustomAgent { max_tokens: Some(1), ..agent() }.validate().is_ok());
+        assert!(CustomAgent { max_tokens: None, ..agent() }.validate().is_ok());
+    }
+
+    #[test]
+    fn blank_fields_and_bad_prompts_are_rejected() {
+        assert_eq!(CustomAgent { name: " ".to_string(), ..agent() }.validate().unwrap_err(), "name must not be empty");
+        assert_eq!(CustomAgent { shortcut: Some(String::new()), ..agent() }.validate().unwrap_err(), "shortcut must not be empty");
+        assert_eq!(CustomAgent { model: Some(String::new()), ..agent() }.validate().unwrap_err(), "model must not be empty");
+
+        let prompt = PromptTemplate { system: "Summarize.".to_string(), user: "Go.".to_string() };
+        assert_eq!(CustomAgent { prompt, ..agent() }.validate().unwrap_err(), "prompt: template must contain {{text}}");
+    }
+
+    #[test]
+    fn parse_reports_malformed_files() {
+        assert!(parse_agent("{ \"name\": \"Missing everything\" }", "broken").is_err());
+        assert!(parse_agent(&AGENT.replace("\"clipboard\"", "\"printer\""), "broken").is_err());
+    }
+}
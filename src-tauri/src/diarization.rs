@@ -0,0 +1,602 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dsp::Biquad;
+use crate::RecordingPause;
+
+// Analysis runs at 16 kHz mono regardless of the capture format
+const ANALYSIS_RATE: u32 = 16000;
+const FRAME_SECONDS: f32 = 0.02;
+const MIN_SPEECH_SECONDS: f32 = 0.3;
+const MAX_GAP_SECONDS: f32 = 0.3;
+const EMBEDDING_WINDOW_SECONDS: f32 = 1.5;
+const MAX_EMBEDDING_WINDOWS: usize = 600;
+const EMBEDDING_BANDS: usize = 16;
+const CLUSTER_DISTANCE_THRESHOLD: f32 = 0.35;
+// Embeddings closer than this (in log band energy) are one voice; cosine distance
+// between their centered residuals would only measure noise
+const SAME_VOICE_DISTANCE: f32 = 0.5;
+
+pub const MICROPHONE_SPEAKER: &str = "You";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioSource {
+    Microphone,
+    System,
+}
+
+/// A timestamped piece of text as returned by Whisper's `verbose_json` format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerSegment {
+    pub speaker: String,
+    pub source: AudioSource,
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// A stretch of audio attributed to one speaker cluster.
+#[derive(Debug, Clone, Copy)]
+pub struct SpeakerTurn {
+    pub start: f32,
+    pub end: f32,
+    pub speaker: usize,
+}
+
+/// Averages interleaved channels down to a single mono track.
+pub fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return samples.to_vec();
+    }
+
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Downmixes and decimates a capture to the 16 kHz analysis rate.
+/// Returns the samples together with the rate they ended up at.
+pub fn prepare_for_analysis(samples: &[f32], sample_rate: u32, channels: u16) -> (Vec<f32>, u32) {
+    let mono = downmix(samples, channels);
+    if sample_rate <= ANALYSIS_RATE {
+        return (mono, sample_rate);
+    }
+
+    // Box-filter decimation is crude but plenty for energy-based features
+    let ratio = sample_rate as f64 / ANALYSIS_RATE as f64;
+    let output_len = (mono.len() as f64 / ratio) as usize;
+    let mut output = Vec::with_capacity(output_len);
+    for i in 0..output_len {
+        let start = (i as f64 * ratio) as usize;
+        let end = (((i + 1) as f64 * ratio) as usize).min(mono.len()).max(start + 1);
+        let window = &mono[start..end];
+        output.push(window.iter().sum::<f32>() / window.len() as f32);
+    }
+    (output, ANALYSIS_RATE)
+}
+
+fn frame_energies_db(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let frame_len = ((sample_rate as f32 * FRAME_SECONDS) as usize).max(1);
+    samples
+        .chunks(frame_len)
+        .map(|frame| {
+            let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+            10.0 * (mean_square + 1e-10).log10()
+        })
+        .collect()
+}
+
+/// Finds the regions (in seconds) that contain speech using an adaptive energy threshold.
+pub fn detect_speech_regions(samples: &[f32], sample_rate: u32) -> Vec<(f32, f32)> {
+    let energies = frame_energies_db(samples, sample_rate);
+    if energies.is_empty() {
+        return Vec::new();
+    }
+
+    // The 10th percentile is a decent estimate of the room's noise floor
+    let mut sorted = energies.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let noise_floor = sorted[sorted.len() / 10];
+    let threshold = (noise_floor + 12.0).max(-55.0);
+
+    let mut regions: Vec<(f32, f32)> = Vec::new();
+    let mut current_start: Option<usize> = None;
+    for (i, &energy) in energies.iter().enumerate() {
+        match (energy > threshold, current_start) {
+            (true, None) => current_start = Some(i),
+            (false, Some(start)) => {
+                regions.push((start as f32 * FRAME_SECONDS, i as f32 * FRAME_SECONDS));
+                current_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = current_start {
+        regions.push((start as f32 * FRAME_SECONDS, energies.len() as f32 * FRAME_SECONDS));
+    }
+
+    // Bridge short pauses, then drop clicks and other blips
+    let mut merged: Vec<(f32, f32)> = Vec::new();
+    for region in regions {
+        match merged.last_mut() {
+            Some(last) if region.0 - last.1 < MAX_GAP_SECONDS => last.1 = region.1,
+            _ => merged.push(region),
+        }
+    }
+    merged.retain(|(start, end)| end - start >= MIN_SPEECH_SECONDS);
+    merged
+}
+
+fn band_centers(sample_rate: u32) -> Vec<f32> {
+    let low = 150.0f32;
+    let high = (sample_rate as f32 * 0.45).min(6000.0);
+    (0..EMBEDDING_BANDS)
+        .map(|i| low * (high / low).powf(i as f32 / (EMBEDDING_BANDS - 1) as f32))
+        .collect()
+}
+
+/// Computes a speaker embedding from the spectral envelope of a speech window:
+/// level-normalized mean log band energies followed by their frame-to-frame spread.
+pub fn compute_embedding(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let frame_len = ((sample_rate as f32 * FRAME_SECONDS) as usize).max(1);
+    let frame_count = (samples.len() / frame_len).max(1);
+
+    let mut means = Vec::with_capacity(EMBEDDING_BANDS);
+    let mut spreads = Vec::with_capacity(EMBEDDING_BANDS);
+    for center in band_centers(sample_rate) {
+        let mut filter = Biquad::band_pass(sample_rate, center, 2.0);
+        let mut frame_energies = vec![0.0f32; frame_count];
+        for (i, &sample) in samples.iter().enumerate() {
+            let filtered = filter.process(sample);
+            let frame = (i / frame_len).min(frame_count - 1);
+            frame_energies[frame] += filtered * filtered;
+        }
+
+        let log_energies: Vec<f32> = frame_energies
+            .iter()
+            .map(|energy| (energy / frame_len as f32 + 1e-10).ln())
+            .collect();
+        let mean = log_energies.iter().sum::<f32>() / log_energies.len() as f32;
+        let variance = log_energies.iter().map(|e| (e - mean).powi(2)).sum::<f32>() / log_energies.len() as f32;
+        means.push(mean);
+        spreads.push(variance.sqrt());
+    }
+
+    // Remove the overall level so loudness doesn't dominate the comparison
+    let level = means.iter().sum::<f32>() / means.len() as f32;
+    let mut embedding: Vec<f32> = means.iter().map(|m| m - level).collect();
+    embedding.extend(spreads);
+    embedding
+}
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 1.0;
+    }
+    1.0 - dot / (norm_a * norm_b)
+}
+
+fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+/// Average-linkage agglomerative clustering. Returns one cluster index per embedding,
+/// numbered in order of first appearance.
+pub fn cluster_embeddings(embeddings: &[Vec<f32>], max_speakers: Option<usize>) -> Vec<usize> {
+    if embeddings.is_empty() {
+        return Vec::new();
+    }
+
+    // Subtract the recording-wide mean so channel coloration cancels out
+    let dims = embeddings[0].len();
+    let mut centroid = vec![0.0f32; dims];
+    for embedding in embeddings {
+        for (c, v) in centroid.iter_mut().zip(embedding) {
+            *c += v / embeddings.len() as f32;
+        }
+    }
+    let normalized: Vec<Vec<f32>> = embeddings
+        .iter()
+        .map(|e| e.iter().zip(&centroid).map(|(v, c)| v - c).collect())
+        .collect();
+
+    // Pairwise distances, updated in place with the Lance-Williams rule as clusters merge
+    let count = normalized.len();
+    let mut distances = vec![vec![0.0f32; count]; count];
+    for i in 0..count {
+        for j in (i + 1)..count {
+            let distance = if euclidean_distance(&embeddings[i], &embeddings[j]) < SAME_VOICE_DISTANCE {
+                0.0
+            } else {
+                cosine_distance(&normalized[i], &normalized[j])
+            };
+            distances[i][j] = distance;
+            distances[j][i] = distance;
+        }
+    }
+
+    let mut members: Vec<Vec<usize>> = (0..count).map(|i| vec![i]).collect();
+    let mut active: Vec<bool> = vec![true; count];
+    let mut remaining = count;
+    let max_speakers = max_speakers.unwrap_or(usize::MAX).max(1);
+
+    while remaining > 1 {
+        let mut best = (f32::MAX, 0, 0);
+        for i in (0..count).filter(|&i| active[i]) {
+            for j in ((i + 1)..count).filter(|&j| active[j]) {
+                if distances[i][j] < best.0 {
+                    best = (distances[i][j], i, j);
+                }
+            }
+        }
+
+        let (distance, i, j) = best;
+        if distance > CLUSTER_DISTANCE_THRESHOLD && remaining <= max_speakers {
+            break;
+        }
+
+        let size_i = members[i].len() as f32;
+        let size_j = members[j].len() as f32;
+        for k in (0..count).filter(|&k| active[k] && k != i && k != j) {
+            let merged = (size_i * distances[i][k] + size_j * distances[j][k]) / (size_i + size_j);
+            distances[i][k] = merged;
+            distances[k][i] = merged;
+        }
+        let absorbed = std::mem::take(&mut members[j]);
+        members[i].extend(absorbed);
+        active[j] = false;
+        remaining -= 1;
+    }
+
+    let mut labels = vec![0usize; count];
+    for (label, cluster) in members.iter().enumerate() {
+        for &member in cluster {
+            labels[member] = label;
+        }
+    }
+
+    // Renumber so the first voice heard is speaker 0
+    let mut order: Vec<usize> = Vec::new();
+    for &label in &labels {
+        if !order.contains(&label) {
+            order.push(label);
+        }
+    }
+    labels
+        .iter()
+        .map(|label| order.iter().position(|l| l == label).unwrap_or(0))
+        .collect()
+}
+
+/// Splits the speech in a mono track into windows and clusters them by voice.
+pub fn diarize(samples: &[f32], sample_rate: u32, max_speakers: Option<usize>) -> Vec<SpeakerTurn> {
+    let regions = detect_speech_regions(samples, sample_rate);
+
+    // Long meetings get longer windows so clustering stays tractable
+    let speech_seconds: f32 = regions.iter().map(|(start, end)| end - start).sum();
+    let window_seconds = EMBEDDING_WINDOW_SECONDS.max(speech_seconds / MAX_EMBEDDING_WINDOWS as f32);
+    let window_len = (sample_rate as f32 * window_seconds) as usize;
+    let mut windows: Vec<(f32, f32)> = Vec::new();
+    let mut embeddings: Vec<Vec<f32>> = Vec::new();
+
+    for (start, end) in regions {
+        let first = (start * sample_rate as f32) as usize;
+        let last = ((end * sample_rate as f32) as usize).min(samples.len());
+        let mut offset = first;
+        while offset < last {
+            // Fold a short tail into the previous window instead of embedding a fragment
+            let window_end = if last - offset < window_len + window_len / 2 { last } else { offset + window_len };
+            embeddings.push(compute_embedding(&samples[offset..window_end], sample_rate));
+            windows.push((offset as f32 / sample_rate as f32, window_end as f32 / sample_rate as f32));
+            offset = window_end;
+        }
+    }
+
+    let labels = cluster_embeddings(&embeddings, max_speakers);
+    windows
+        .into_iter()
+        .zip(labels)
+        .map(|((start, end), speaker)| SpeakerTurn { start, end, speaker })
+        .collect()
+}
+
+/// Picks the speaker whose turns overlap the segment the most, falling back to the nearest turn.
+fn speaker_for_segment(segment: &TranscriptSegment, turns: &[SpeakerTurn]) -> usize {
+    let mut overlaps: Vec<(usize, f32)> = Vec::new();
+    for turn in turns {
+        let overlap = segment.end.min(turn.end) - segment.start.max(turn.start);
+        if overlap > 0.0 {
+            match overlaps.iter_mut().find(|(speaker, _)| *speaker == turn.speaker) {
+                Some(entry) => entry.1 += overlap,
+                None => overlaps.push((turn.speaker, overlap)),
+            }
+        }
+    }
+
+    if let Some((speaker, _)) = overlaps
+        .iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    {
+        return *speaker;
+    }
+
+    let midpoint = (segment.start + segment.end) / 2.0;
+    turns
+        .iter()
+        .min_by(|a, b| {
+            let distance_a = (midpoint - (a.start + a.end) / 2.0).abs();
+            let distance_b = (midpoint - (b.start + b.end) / 2.0).abs();
+            distance_a.partial_cmp(&distance_b).unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|turn| turn.speaker)
+        .unwrap_or(0)
+}
+
+/// Labels system-audio transcript segments with the clustered speakers.
+pub fn label_system_segments(segments: Vec<TranscriptSegment>, turns: &[SpeakerTurn]) -> Vec<SpeakerSegment> {
+    segments
+        .into_iter()
+        .map(|segment| {
+            let speaker = speaker_for_segment(&segment, turns);
+            SpeakerSegment {
+                speaker: format!("Speaker {}", speaker + 1),
+                source: AudioSource::System,
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.trim().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Labels microphone transcript segments as the local user.
+pub fn label_microphone_segments(segments: Vec<TranscriptSegment>) -> Vec<SpeakerSegment> {
+    segments
+        .into_iter()
+        .map(|segment| SpeakerSegment {
+            speaker: MICROPHONE_SPEAKER.to_string(),
+            source: AudioSource::Microphone,
+            start: segment.start,
+            end: segment.end,
+            text: segment.text.trim().to_string(),
+        })
+        .collect()
+}
+
+/// Interleaves both tracks chronologically and drops empty segments.
+pub fn merge_tracks(mut microphone: Vec<SpeakerSegment>, system: Vec<SpeakerSegment>) -> Vec<SpeakerSegment> {
+    microphone.extend(system);
+    microphone.retain(|segment| !segment.text.is_empty());
+    microphone.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap_or(std::cmp::Ordering::Equal));
+    microphone
+}
+
+fn format_timestamp(seconds: f32) -> String {
+    let total = seconds.max(0.0) as u32;
+    format!("{:02}:{:02}", total / 60, total % 60)
+}
+
+fn format_pause(pause: &RecordingPause) -> String {
+    format!("[{}] ⏸️ Recording paused for {:.0}s", format_timestamp(pause.at_seconds), pause.duration_seconds)
+}
+
+/// Renders Whisper segments as running text, with a pause line wherever the recording was paused.
+pub fn format_plain_transcript(segments: &[TranscriptSegment], pauses: &[RecordingPause]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut pending_pauses = pauses.iter().peekable();
+
+    for segment in segments {
+        while let Some(pause) = pending_pauses.next_if(|pause| pause.at_seconds <= segment.start) {
+            if !paragraph.is_empty() {
+                lines.push(paragraph.join(" "));
+                paragraph.clear();
+            }
+            lines.push(format_pause(pause));
+        }
+        let text = segment.text.trim();
+        if !text.is_empty() {
+            paragraph.push(text);
+        }
+    }
+
+    if !paragraph.is_empty() {
+        lines.push(paragraph.join(" "));
+    }
+    lines.extend(pending_pauses.map(format_pause));
+    lines.join("\n")
+}
+
+/// Renders segments as a speaker-labeled transcript, merging consecutive segments of the same speaker.
+/// Pauses are inserted where they happened and always break a speaker's run.
+pub fn format_speaker_transcript(segments: &[SpeakerSegment], pauses: &[RecordingPause]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current: Option<(String, f32, String)> = None;
+    let mut pending_pauses = pauses.iter().peekable();
+
+    let render = |(speaker, start, text): (String, f32, String)| format!("[{}] {}: {}", format_timestamp(start), speaker, text);
+
+    for segment in segments {
+        while let Some(pause) = pending_pauses.next_if(|pause| pause.at_seconds <= segment.start) {
+            if let Some(line) = current.take() {
+                lines.push(render(line));
+            }
+            lines.push(format_pause(pause));
+        }
+
+        match &mut current {
+            Some((speaker, _, text)) if *speaker == segment.speaker => {
+                text.push(' ');
+                text.push_str(&segment.text);
+            }
+            _ => {
+                if let Some(line) = current.take() {
+                    lines.push(render(line));
+                }
+                current = Some((segment.speaker.clone(), segment.start, segment.text.clone()));
+            }
+        }
+    }
+
+    if let Some(line) = current.take() {
+        lines.push(render(line));
+    }
+    lines.extend(pending_pauses.map(format_pause));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A steady synthetic voice: harmonics of `pitch` shaped by a single formant at `formant` Hz,
+    /// with a little noise so no two windows are identical.
+    fn voice(pitch: f32, formant: f32, seconds: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        let harmonics = ((ANALYSIS_RATE as f32 * 0.45) / pitch) as usize;
+        (0..(ANALYSIS_RATE as f32 * seconds) as usize)
+            .map(|i| {
+                let t = i as f32 / ANALYSIS_RATE as f32;
+                let tone: f32 = (1..=harmonics)
+                    .map(|k| {
+                        let frequency = pitch * k as f32;
+                        let gain = 1.0 / (1.0 + ((frequency - formant) / 300.0).powi(2));
+                        gain * (2.0 * std::f32::consts::PI * frequency * t).sin()
+                    })
+                    .sum();
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                0.1 * tone + 0.01 * noise
+            })
+            .collect()
+    }
+
+    fn segment(speaker: &str, start: f32, text: &str) -> SpeakerSegment {
+        SpeakerSegment {
+            speaker: speaker.to_string(),
+            source: if speaker == MICROPHONE_SPEAKER { AudioSource::Microphone } else { AudioSource::System },
+            start,
+            end: start + 1.0,
+            text: text.to_string(),
+        }
+    }
+
+    fn pause(at_seconds: f32, duration_seconds: f32) -> RecordingPause {
+        RecordingPause { at_seconds, duration_seconds }
+    }
+
+    /// Three tight groups of vectors pointing in different directions.
+    fn three_groups() -> Vec<Vec<f32>> {
+        vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.98, 0.05, 0.0],
+            vec![0.0, 0.0, 1.0],
+            vec![0.05, 0.97, 0.0],
+            vec![0.0, 0.04, 0.99],
+        ]
+    }
+
+    #[test]
+    fn embeddings_of_two_voices_form_two_clusters() {
+        let embeddings: Vec<Vec<f32>> = (0..6)
+            .map(|i| {
+                let samples = if i % 2 == 0 { voice(110.0, 500.0, 1.5, i) } else { voice(230.0, 2500.0, 1.5, i) };
+                compute_embedding(&samples, ANALYSIS_RATE)
+            })
+            .collect();
+
+        let labels = cluster_embeddings(&embeddings, None);
+        assert_eq!(labels, vec![0, 1, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn clustering_stops_at_the_distance_threshold() {
+        let labels = cluster_embeddings(&three_groups(), None);
+        assert_eq!(labels, vec![0, 1, 0, 2, 1, 2]);
+
+        // Windows of a single voice differ only by noise and stay one speaker
+        let one: Vec<Vec<f32>> = (0..6).map(|i| compute_embedding(&voice(110.0, 500.0, 1.5, i), ANALYSIS_RATE)).collect();
+        assert_eq!(cluster_embeddings(&one, None), vec![0; 6]);
+        assert_eq!(cluster_embeddings(&vec![vec![0.3, 0.2, 0.1]; 4], None), vec![0; 4]);
+    }
+
+    #[test]
+    fn clustering_merges_past_the_threshold_to_honour_the_speaker_limit() {
+        let labels = cluster_embeddings(&three_groups(), Some(2));
+        assert_eq!(labels.iter().max(), Some(&1));
+        // The tight groups stay together when two of them are merged
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[1], labels[4]);
+        assert_eq!(labels[3], labels[5]);
+
+        assert_eq!(cluster_embeddings(&three_groups(), Some(1)), vec![0; 6]);
+        // Zero is treated as one speaker rather than leaving everything unclustered
+        assert_eq!(cluster_embeddings(&three_groups(), Some(0)), vec![0; 6]);
+        // A limit above the natural count doesn't split anything further
+        assert_eq!(cluster_embeddings(&three_groups(), Some(5)), vec![0, 1, 0, 2, 1, 2]);
+    }
+
+    #[test]
+    fn clustering_handles_trivial_inputs() {
+        assert!(cluster_embeddings(&[], None).is_empty());
+        assert_eq!(cluster_embeddings(&[vec![1.0, 2.0]], Some(3)), vec![0]);
+    }
+
+    #[test]
+    fn speaker_transcript_merges_runs_and_breaks_them_at_pauses() {
+        let segments = vec![
+            segment(MICROPHONE_SPEAKER, 0.0, "Hello"),
+            segment(MICROPHONE_SPEAKER, 2.0, "there."),
+            segment(MICROPHONE_SPEAKER, 5.0, "Back again."),
+            segment("Speaker 1", 7.0, "Hi!"),
+            segment("Speaker 1", 9.0, "Welcome back."),
+            segment(MICROPHONE_SPEAKER, 65.0, "Thanks."),
+        ];
+        let pauses = vec![pause(3.0, 12.4), pause(5.0, 2.0), pause(80.0, 4.0)];
+
+        assert_eq!(
+            format_speaker_transcript(&segments, &pauses),
+            "[00:00] You: Hello there.\n\
+             [00:03] ⏸️ Recording paused for 12s\n\
+             [00:05] ⏸️ Recording paused for 2s\n\
+             [00:05] You: Back again.\n\
+             [00:07] Speaker 1: Hi! Welcome back.\n\
+             [01:05] You: Thanks.\n\
+             [01:20] ⏸️ Recording paused for 4s"
+        );
+    }
+
+    #[test]
+    fn speaker_transcript_without_pauses() {
+        let segments = vec![segment("Speaker 1", 0.0, "One."), segment("Speaker 2", 1.5, "Two.")];
+        assert_eq!(format_speaker_transcript(&segments, &[]), "[00:00] Speaker 1: One.\n[00:01] Speaker 2: Two.");
+        assert_eq!(format_speaker_transcript(&[], &[pause(0.0, 3.0)]), "[00:00] ⏸️ Recording paused for 3s");
+    }
+
+    #[test]
+    fn plain_transcript_starts_a_paragraph_after_each_pause() {
+        let segment = |start: f32, text: &str| TranscriptSegment { start, end: start + 1.0, text: text.to_string() };
+        let segments = vec![segment(0.0, " First part."), segment(1.0, " "), segment(2.0, "Still first."), segment(10.0, "Second part.")];
+
+        assert_eq!(
+            format_plain_transcript(&segments, &[pause(4.0, 30.0), pause(12.0, 1.0)]),
+            "First part. Still first.\n\
+             [00:04] ⏸️ Recording paused for 30s\n\
+             Second part.\n\
+             [00:12] ⏸️ Recording paused for 1s"
+        );
+    }
+}
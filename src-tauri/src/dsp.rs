@@ -0,0 +1,339 @@
+use serde::{Deserialize, Serialize};
+
+// Noise gate
+const GATE_FRAME_SECONDS: f32 = 0.01;
+const GATE_NOISE_PERCENTILE: f32 = 0.1;
+const GATE_MARGIN_DB: f32 = 8.0;
+const GATE_REDUCTION_DB: f32 = 20.0;
+const GATE_HOLD_SECONDS: f32 = 0.1;
+const GATE_ATTACK_SECONDS: f32 = 0.005;
+const GATE_RELEASE_SECONDS: f32 = 0.05;
+
+// Loudness measurement (ITU-R BS.1770 / EBU R128)
+const LOUDNESS_BLOCK_SECONDS: f32 = 0.4;
+const LOUDNESS_STEP_SECONDS: f32 = 0.1;
+const ABSOLUTE_GATE_LUFS: f32 = -70.0;
+const RELATIVE_GATE_LU: f32 = 10.0;
+const PEAK_CEILING_DB: f32 = -1.0;
+
+/// Which cleanup stages run on audio before it is sent to Whisper.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioProcessingConfig {
+    /// Removes rumble and DC offset below `high_pass_hz`
+    pub high_pass: bool,
+    pub high_pass_hz: f32,
+    /// Attenuates the background between words, relative to the estimated noise floor
+    pub noise_gate: bool,
+    /// Scales the whole recording to `target_lufs` integrated loudness
+    pub normalize: bool,
+    pub target_lufs: f32,
+}
+
+impl Default for AudioProcessingConfig {
+    fn default() -> Self {
+        AudioProcessingConfig {
+            high_pass: true,
+            high_pass_hz: 80.0,
+            noise_gate: true,
+            normalize: true,
+            target_lufs: -16.0,
+        }
+    }
+}
+
+impl AudioProcessingConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(20.0..=300.0).contains(&self.high_pass_hz) {
+            return Err("high_pass_hz must be between 20 and 300".to_string());
+        }
+        if !(-40.0..=-5.0).contains(&self.target_lufs) {
+            return Err("target_lufs must be between -40 and -5".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Second-order IIR section (RBJ audio EQ cookbook coefficients).
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn new(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Biquad {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub fn band_pass(sample_rate: u32, center_hz: f32, q: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * center_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        Biquad::new(alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * w0.cos(), 1.0 - alpha)
+    }
+
+    pub fn high_pass(sample_rate: u32, cutoff_hz: f32, q: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos = w0.cos();
+        Biquad::new((1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+    }
+
+    pub fn high_shelf(sample_rate: u32, corner_hz: f32, gain_db: f32, q: f32) -> Self {
+        let a = 10f32.powf(gain_db / 40.0);
+        let w0 = 2.0 * std::f32::consts::PI * corner_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos = w0.cos();
+        let shelf = 2.0 * a.sqrt() * alpha;
+        Biquad::new(
+            a * ((a + 1.0) + (a - 1.0) * cos + shelf),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - shelf),
+            (a + 1.0) - (a - 1.0) * cos + shelf,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - shelf,
+        )
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+}
+
+/// Runs the enabled stages on mono `samples`: high-pass, then noise gate, then loudness normalization.
+pub fn process(samples: &[f32], sample_rate: u32, config: &AudioProcessingConfig) -> Vec<f32> {
+    let mut output = samples.to_vec();
+    if output.is_empty() || sample_rate == 0 {
+        return output;
+    }
+
+    if config.high_pass {
+        high_pass(&mut output, sample_rate, config.high_pass_hz);
+    }
+    if config.noise_gate {
+        noise_gate(&mut output, sample_rate);
+    }
+    if config.normalize {
+        match normalize_loudness(&mut output, sample_rate, config.target_lufs) {
+            Some(gain_db) => println!("🔊 Normalized loudness to {:.0} LUFS ({:+.1} dB)", config.target_lufs, gain_db),
+            None => println!("🔇 Audio too quiet to normalize"),
+        }
+    }
+    output
+}
+
+/// Butterworth high-pass in place.
+pub fn high_pass(samples: &mut [f32], sample_rate: u32, cutoff_hz: f32) {
+    let mut filter = Biquad::high_pass(sample_rate, cutoff_hz, std::f32::consts::FRAC_1_SQRT_2);
+    for sample in samples.iter_mut() {
+        *sample = filter.process(*sample);
+    }
+}
+
+/// Downward gate: frames that stay close to the noise floor are attenuated by `GATE_REDUCTION_DB`,
+/// with hold and attack/release smoothing so word onsets and tails are not clipped.
+pub fn noise_gate(samples: &mut [f32], sample_rate: u32) {
+    if samples.is_empty() {
+        return;
+    }
+    let frame_len = ((sample_rate as f32 * GATE_FRAME_SECONDS) as usize).max(1);
+    let frame_db: Vec<f32> = samples
+        .chunks(frame_len)
+        .map(|frame| {
+            let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+            10.0 * (mean_square + 1e-10).log10()
+        })
+        .collect();
+
+    let mut sorted = frame_db.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let noise_floor = sorted[((sorted.len() - 1) as f32 * GATE_NOISE_PERCENTILE) as usize];
+    let threshold = noise_floor + GATE_MARGIN_DB;
+
+    // Without quiet gaps there is no noise floor to separate from, so leave the signal alone
+    let loud = sorted[((sorted.len() - 1) as f32 * (1.0 - GATE_NOISE_PERCENTILE)) as usize];
+    if loud < threshold {
+        return;
+    }
+
+    let hold_frames = (GATE_HOLD_SECONDS / GATE_FRAME_SECONDS) as usize;
+    let closed_gain = 10f32.powf(-GATE_REDUCTION_DB / 20.0);
+    let mut frames_since_open = usize::MAX;
+    let targets: Vec<f32> = frame_db
+        .iter()
+        .map(|&db| {
+            if db >= threshold {
+                frames_since_open = 0;
+            } else {
+                frames_since_open = frames_since_open.saturating_add(1);
+            }
+            if frames_since_open <= hold_frames { 1.0 } else { closed_gain }
+        })
+        .collect();
+
+    let attack = 1.0 - (-1.0 / (GATE_ATTACK_SECONDS * sample_rate as f32)).exp();
+    let release = 1.0 - (-1.0 / (GATE_RELEASE_SECONDS * sample_rate as f32)).exp();
+    let mut gain = targets[0];
+    for (i, sample) in samples.iter_mut().enumerate() {
+        let target = targets[i / frame_len];
+        let coefficient = if target > gain { attack } else { release };
+        gain += (target - gain) * coefficient;
+        *sample *= gain;
+    }
+}
+
+/// Integrated loudness in LUFS with the BS.1770 absolute and relative gates, or `None` for silence.
+pub fn measure_loudness(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    // K-weighting: a head-related high shelf followed by the RLB high-pass
+    let mut shelf = Biquad::high_shelf(sample_rate, 1681.97, 4.0, std::f32::consts::FRAC_1_SQRT_2);
+    let mut rlb = Biquad::high_pass(sample_rate, 38.13, 0.5);
+    let weighted: Vec<f32> = samples.iter().map(|&s| rlb.process(shelf.process(s))).collect();
+
+    let block_len = ((sample_rate as f32 * LOUDNESS_BLOCK_SECONDS) as usize).clamp(1, weighted.len().max(1));
+    let step = ((sample_rate as f32 * LOUDNESS_STEP_SECONDS) as usize).max(1);
+    let mut block_powers = Vec::new();
+    let mut start = 0;
+    while start + block_len <= weighted.len() {
+        let block = &weighted[start..start + block_len];
+        block_powers.push(block.iter().map(|s| s * s).sum::<f32>() / block_len as f32);
+        start += step;
+    }
+
+    let loudness = |power: f32| -0.691 + 10.0 * power.max(1e-12).log10();
+    let mean = |powers: &[f32]| powers.iter().sum::<f32>() / powers.len() as f32;
+
+    let audible: Vec<f32> = block_powers.into_iter().filter(|&p| loudness(p) > ABSOLUTE_GATE_LUFS).collect();
+    if audible.is_empty() {
+        return None;
+    }
+    let relative_gate = loudness(mean(&audible)) - RELATIVE_GATE_LU;
+    let gated: Vec<f32> = audible.into_iter().filter(|&p| loudness(p) > relative_gate).collect();
+    Some(loudness(mean(&gated)))
+}
+
+/// Scales `samples` to `target_lufs`, limited so the peak stays below `PEAK_CEILING_DB`.
+/// Returns the applied gain in dB, or `None` if the input is silent.
+pub fn normalize_loudness(samples: &mut [f32], sample_rate: u32, target_lufs: f32) -> Option<f32> {
+    let measured = measure_loudness(samples, sample_rate)?;
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak <= 0.0 {
+        return None;
+    }
+
+    let peak_headroom_db = PEAK_CEILING_DB - 20.0 * peak.log10();
+    let gain_db = (target_lufs - measured).min(peak_headroom_db);
+    let gain = 10f32.powf(gain_db / 20.0);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+    Some(gain_db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48_000;
+
+    fn sine(frequency: f32, amplitude: f32, seconds: f32) -> Vec<f32> {
+        (0..(RATE as f32 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    /// Deterministic white noise in [-amplitude, amplitude].
+    fn noise(amplitude: f32, seconds: f32) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..(RATE as f32 * seconds) as usize)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// RMS after the filter has settled, i.e. over the second half.
+    fn settled_rms(samples: &[f32]) -> f32 {
+        rms(&samples[samples.len() / 2..])
+    }
+
+    #[test]
+    fn high_pass_attenuates_rumble_and_keeps_voice() {
+        let mut rumble = sine(30.0, 0.5, 1.0);
+        let before = settled_rms(&rumble);
+        high_pass(&mut rumble, RATE, 80.0);
+        assert!(settled_rms(&rumble) < before * 0.2, "30 Hz should be attenuated");
+
+        let mut voice = sine(1000.0, 0.5, 1.0);
+        let before = settled_rms(&voice);
+        high_pass(&mut voice, RATE, 80.0);
+        assert!((settled_rms(&voice) / before - 1.0).abs() < 0.05, "1 kHz should pass unchanged");
+    }
+
+    #[test]
+    fn noise_gate_removes_background_and_keeps_speech() {
+        let second = RATE as usize;
+        let mut samples = noise(0.002, 1.0);
+        samples.extend(sine(300.0, 0.3, 1.0).iter().zip(noise(0.002, 1.0)).map(|(s, n)| s + n));
+        samples.extend(noise(0.002, 1.0));
+        let original = samples.clone();
+
+        noise_gate(&mut samples, RATE);
+
+        // Away from the hold and release around the speech
+        let quiet = 0..second / 2;
+        let speech = second + second / 4..2 * second - second / 4;
+        assert!(rms(&samples[quiet.clone()]) < rms(&original[quiet]) * 0.2, "background should be attenuated");
+        assert!(rms(&samples[speech.clone()]) > rms(&original[speech]) * 0.95, "speech should be kept");
+    }
+
+    #[test]
+    fn noise_gate_leaves_continuous_signals_alone() {
+        let mut samples = sine(300.0, 0.3, 1.0);
+        let original = samples.clone();
+        noise_gate(&mut samples, RATE);
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn normalize_loudness_reaches_target() {
+        let mut samples = sine(1000.0, 0.02, 3.0);
+        let gain_db = normalize_loudness(&mut samples, RATE, -16.0).unwrap();
+        assert!(gain_db > 0.0);
+        let measured = measure_loudness(&samples, RATE).unwrap();
+        assert!((measured + 16.0).abs() < 0.5, "measured {} LUFS", measured);
+    }
+
+    #[test]
+    fn normalize_loudness_respects_peak_ceiling_and_silence() {
+        // A single click is quiet on average but must not be boosted past the ceiling
+        let mut samples = vec![0.0; RATE as usize * 2];
+        samples[RATE as usize] = 0.5;
+        samples.extend(sine(1000.0, 0.001, 1.0));
+        normalize_loudness(&mut samples, RATE, -16.0);
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(20.0 * peak.log10() <= PEAK_CEILING_DB + 0.01);
+
+        let mut silence = vec![0.0; RATE as usize];
+        assert_eq!(normalize_loudness(&mut silence, RATE, -16.0), None);
+    }
+}
@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::{credentials, settings, ResponseMetadata};
+
+/// Keyword hits a language needs before a snippet is attributed to it.
+const MIN_LANGUAGE_SCORE: usize = 2;
+
+/// Telltale substrings per language, with the Markdown fence tag used for code blocks.
+const LANGUAGE_MARKERS: &[(&str, &[&str])] = &[
+    ("rust", &["fn ", "let mut ", "impl ", "pub fn", "::", "-> ", "&str", "Option<", "Result<", "match ", "#[derive"]),
+    ("python", &["def ", "import ", "self.", "elif ", "None", "print(", "__init__", "lambda ", "):\n"]),
+    ("typescript", &["interface ", ": string", ": number", "=> ", "export ", "const ", "readonly ", "<T>"]),
+    ("javascript", &["function ", "const ", "let ", "=> ", "console.log", "require(", "module.exports", "===", "undefined"]),
+    ("java", &["public class ", "public static void", "private ", "System.out", "import java.", "@Override", "new ", "String[]"]),
+    ("csharp", &["using System", "namespace ", "public class ", "Console.Write", "var ", "async Task", "get; set;"]),
+    ("go", &["func ", "package ", ":= ", "fmt.", "err != nil", "go ", "chan ", "defer "]),
+    ("c", &["#include", "int main(", "printf(", "malloc(", "->", "NULL", "sizeof("]),
+    ("cpp", &["#include", "std::", "cout", "template<", "nullptr", "namespace ", "::"]),
+    ("sql", &["SELECT ", "FROM ", "WHERE ", "JOIN ", "INSERT INTO", "GROUP BY", "CREATE TABLE"]),
+    ("bash", &["#!/bin/", "echo ", "fi\n", "then\n", "$(", "export ", "| grep"]),
+    ("html", &["<div", "</", "<html", "class=\"", "<span", "<script"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    Code,
+    StackTrace,
+}
+
+/// What the clipboard holds; `language` is a Markdown fence tag such as "rust" or "python".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedCode {
+    pub kind: ContentKind,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplainTask {
+    Explain,
+    FindBugs,
+    DocComments,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExplainRequest {
+    pub text: String,
+    /// What to do with the code; explaining when omitted
+    pub task: Option<ExplainTask>,
+    /// Overrides the detected language
+    pub language: Option<String>,
+    pub credential: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainResult {
+    pub markdown: String,
+    pub task: ExplainTask,
+    pub detected: DetectedCode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainResponse {
+    #[serde(flatten)]
+    pub result: ExplainResult,
+    pub metadata: ResponseMetadata,
+}
+
+/// Recognizes the stack trace formats of common runtimes.
+fn stack_trace_language(text: &str) -> Option<Option<&'static str>> {
+    let has_frames = |suffix: &str| text.lines().any(|line| line.trim_start().starts_with("at ") && line.contains(suffix));
+    if text.contains("Traceback (most recent call last)") {
+        Some(Some("python"))
+    } else if text.contains("panicked at") && (text.contains("thread '") || text.contains("stack backtrace")) {
+        Some(Some("rust"))
+    } else if text.contains("goroutine ") && text.contains("[running]") {
+        Some(Some("go"))
+    } else if has_frames(".java:") || text.contains("Exception in thread \"") {
+        Some(Some("java"))
+    } else if has_frames(".cs:line") {
+        Some(Some("csharp"))
+    } else if has_frames(".ts:") {
+        Some(Some("typescript"))
+    } else if has_frames(".js:") || has_frames("node:internal") {
+        Some(Some("javascript"))
+    } else if text.lines().filter(|line| line.trim_start().starts_with("at ")).count() >= 3 {
+        Some(None)
+    } else {
+        None
+    }
+}
+
+/// Detects stack traces and the programming language of a snippet locally, from telltale keywords.
+pub fn detect(text: &str) -> DetectedCode {
+    if let Some(language) = stack_trace_language(text) {
+        return DetectedCode {
+            kind: ContentKind::StackTrace,
+            language: language.map(str::to_string),
+        };
+    }
+
+    let language = LANGUAGE_MARKERS.iter()
+        .map(|(language, markers)| (*language, markers.iter().filter(|marker| text.contains(*marker)).count()))
+        // Ties go to the language listed first (e.g. TypeScript over JavaScript)
+        .fold(None, |best: Option<(&str, usize)>, candidate| match best {
+            Some(best) if best.1 >= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })
+        .filter(|(_, score)| *score >= MIN_LANGUAGE_SCORE)
+        .map(|(language, _)| language.to_string());
+    DetectedCode { kind: ContentKind::Code, language }
+}
+
+/// How documentation comments are written in each language.
+fn doc_comment_convention(language: &str) -> &'static str {
+    match language {
+        "rust" => "`///` doc comments (and `//!` for modules)",
+        "python" => "PEP 257 docstrings",
+        "javascript" | "typescript" => "JSDoc `/** ... */` comments",
+        "java" => "Javadoc `/** ... */` comments with @param and @return",
+        "csharp" => "XML `///` documentation comments",
+        "go" => "`//` comments that start with the name of the declaration",
+        _ => "the idiomatic documentation comment style of the language",
+    }
+}
+
+fn system_prompt(task: ExplainTask, detected: &DetectedCode) -> String {
+    let language = detected.language.as_deref();
+    let name = language.unwrap_or("the programming language you recognize");
+    let fence = language.unwrap_or("the language name");
+    let task_prompt = match (detected.kind, task) {
+        (ContentKind::StackTrace, ExplainTask::Explain) => format!(
+            "The user sends a stack trace from {}. Explain what went wrong in plain words, point to the frame that most likely belongs to the user's own code, list the probable root causes from most to least likely, and suggest how to fix or further diagnose each one.",
+            name
+        ),
+        (ContentKind::StackTrace, _) => format!(
+            "The user sends a stack trace from {}. Identify the bug it reveals and suggest concrete fixes.",
+            name
+        ),
+        (ContentKind::Code, ExplainTask::Explain) => format!(
+            "The user sends a snippet of {} code. Explain what it does: start with a one-paragraph overview, then walk through the important parts. Mention non-obvious language features and any side effects.",
+            name
+        ),
+        (ContentKind::Code, ExplainTask::FindBugs) => format!(
+            "You are a careful code reviewer. The user sends a snippet of {} code. List real bugs first (logic errors, edge cases, error handling, concurrency, security), then risky or unclear code, most severe first. For each, quote the relevant line, explain the problem and show a fix. If you find nothing significant, say so instead of inventing issues.",
+            name
+        ),
+        (ContentKind::Code, ExplainTask::DocComments) => format!(
+            "The user sends a snippet of {} code. Return the same code with documentation comments added to every function, type and non-obvious block, using {}. Do not change the code itself. Follow the code block with a short note on anything that was unclear to document.",
+            name,
+            doc_comment_convention(language.unwrap_or(""))
+        ),
+    };
+    format!(
+        "{} Answer in Markdown. Put every code excerpt in a fenced code block tagged with its language (```{}) so it can be syntax-highlighted.",
+        task_prompt, fence
+    )
+}
+
+fn payload(settings: &settings::Settings, text: &str, task: ExplainTask, detected: &DetectedCode) -> serde_json::Value {
+    serde_json::json!({
+        "model": settings.model,
+        "messages": [
+            {
+                "role": "system",
+                "content": system_prompt(task, detected)
+            },
+            {
+                "role": "user",
+                "content": text
+            }
+        ],
+        "temperature": settings.temperature,
+        // Doc comments repeat the whole snippet
+        "max_tokens": match task {
+            ExplainTask::DocComments => (text.chars().count() / 2 + 800).min(4000),
+            _ => 1500,
+        },
+        "stream": true
+    })
+}
+
+fn resolve_detection(text: &str, language: Option<String>) -> DetectedCode {
+    let mut detected = detect(text);
+    if let Some(language) = language.map(|language| language.trim().to_lowercase()).filter(|language| !language.is_empty()) {
+        detected.language = Some(language);
+    }
+    detected
+}
+
+async fn explain<F: FnMut(&str)>(
+    api_key: &str,
+    settings: &settings::Settings,
+    text: &str,
+    task: ExplainTask,
+    detected: DetectedCode,
+    on_chunk: F,
+) -> Result<ExplainResult, String> {
+    println!("🧑‍💻 {:?} for {:?} ({}, {} chars)...", task, detected.kind, detected.language.as_deref().unwrap_or("unknown language"), text.len());
+    let markdown = crate::stream_chat_completion(api_key, payload(settings, text, task, &detected), on_chunk).await?;
+    let markdown = markdown.trim().to_string();
+    if markdown.is_empty() {
+        return Err("No explanation in response".to_string());
+    }
+    Ok(ExplainResult { markdown, task, detected })
+}
+
+#[tauri::command]
+pub async fn explain_code(app_handle: tauri::AppHandle, request: ExplainRequest) -> Result<ExplainResponse, String> {
+    if request.text.trim().is_empty() {
+        return Err("No code provided to explain".to_string());
+    }
+    let credential = credentials::resolve(&app_handle, request.credential.as_deref())?;
+    let settings = settings::current(&app_handle);
+
+    let detected = resolve_detection(&request.text, request.language);
+    let task = request.task.unwrap_or(ExplainTask::Explain);
+    let result = explain(&credential.api_key, &settings, &request.text, task, detected, |_| {}).await?;
+    Ok(ExplainResponse {
+        result,
+        metadata: ResponseMetadata {
+            credential: credential.name,
+            model: settings.model,
+        },
+    })
+}
+
+/// Opens the code window and streams the explanation of `text` into it; the window can re-run other tasks.
+#[tauri::command]
+pub async fn show_code_window(app_handle: tauri::AppHandle, text: String, task: Option<ExplainTask>) -> Result<(), String> {
+    let task = task.unwrap_or(ExplainTask::Explain);
+    // Fail fast before opening a window without a usable key
+    let credential = credentials::resolve(&app_handle, None)?;
+    let settings = settings::current(&app_handle);
+
+    let window = tauri::WebviewWindowBuilder::new(
+        &app_handle,
+        "code-explainer",
+        tauri::WebviewUrl::App("code-explainer.html".into())
+    )
+    .title("Code Explainer")
+    .inner_size(800.0, 650.0)
+    .center()
+    .resizable(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    // Wait for window to load before sending events
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+
+    let detected = detect(&text);
+    let _ = window.emit("clipboard-text", &text);
+    let _ = window.emit("code-detected", &detected);
+    let _ = window.emit("code-task", task);
+    let _ = window.emit("code-metadata", &ResponseMetadata {
+        credential: credential.name.clone(),
+        model: settings.model.clone(),
+    });
+
+    let result = explain(&credential.api_key, &settings, &text, task, detected, |chunk| {
+        let _ = window.emit("code-chunk", chunk);
+    })
+    .await;
+    match result {
+        Ok(result) => {
+            println!("🎉 CODE EXPLANATION SUCCESS: {} chars", result.markdown.len());
+            let _ = window.emit("code-complete", &result);
+        }
+        Err(e) => {
+            println!("❌ Code explanation failed: {}", e);
+            let _ = window.emit("code-error", &e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,639 @@
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+const GLOSSARY_FILE: &str = "glossary.json";
+
+lazy_static::lazy_static! {
+    // Serializes read-modify-write cycles on the glossary file
+    static ref GLOSSARY_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Language names the translator uses, with the codes TBX files carry.
+const LANGUAGE_CODES: &[(&str, &str)] = &[
+    ("English", "en"),
+    ("German", "de"),
+    ("French", "fr"),
+    ("Spanish", "es"),
+    ("Italian", "it"),
+    ("Portuguese", "pt"),
+    ("Dutch", "nl"),
+    ("Polish", "pl"),
+    ("Russian", "ru"),
+    ("Arabic", "ar"),
+    ("Chinese", "zh"),
+    ("Japanese", "ja"),
+    ("Korean", "ko"),
+];
+
+/// A fixed translation of `source` whenever the target is `language`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub source: String,
+    pub target: String,
+    /// Target language name as used in translation requests, e.g. "German"
+    pub language: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Glossary {
+    /// Language the source terms are written in (used for TBX export)
+    pub source_language: String,
+    pub entries: Vec<GlossaryEntry>,
+    /// Product names and identifiers that must appear verbatim in every translation
+    pub do_not_translate: Vec<String>,
+}
+
+impl Default for Glossary {
+    fn default() -> Self {
+        Glossary {
+            source_language: "English".to_string(),
+            entries: Vec::new(),
+            do_not_translate: Vec::new(),
+        }
+    }
+}
+
+/// A glossary term the model did not honour.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryViolation {
+    pub term: String,
+    pub expected: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlossaryFormat {
+    Csv,
+    Tbx,
+}
+
+impl GlossaryFormat {
+    fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()).as_deref() {
+            Some("csv") => Ok(GlossaryFormat::Csv),
+            Some("tbx") | Some("xml") => Ok(GlossaryFormat::Tbx),
+            _ => Err(format!("Can't tell the glossary format of {}; use a .csv or .tbx file", path.display())),
+        }
+    }
+}
+
+impl Glossary {
+    pub fn validate(&self) -> Result<(), String> {
+        for entry in &self.entries {
+            if entry.source.trim().is_empty() || entry.target.trim().is_empty() || entry.language.trim().is_empty() {
+                return Err(format!("Glossary entry \"{}\" needs a source term, a target term and a language", entry.source));
+            }
+        }
+        if self.do_not_translate.iter().any(|term| term.trim().is_empty()) {
+            return Err("Do-not-translate terms must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Fingerprint of the glossary contents, so cached translations made under other terms are not reused.
+    pub fn version(&self) -> String {
+        // FNV-1a: stable across builds, unlike the std hasher
+        let contents = serde_json::to_string(self).unwrap_or_default();
+        let hash = contents.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+        format!("{:016x}", hash)
+    }
+
+    /// Entries for `language` whose source term occurs in `text`.
+    fn relevant_entries<'a>(&'a self, language: &'a str, text: &'a str) -> impl Iterator<Item = &'a GlossaryEntry> + 'a {
+        self.entries.iter().filter(move |entry| entry.language.eq_ignore_ascii_case(language) && contains_term(text, &entry.source))
+    }
+
+    /// Do-not-translate terms that occur in `text`.
+    fn relevant_terms<'a>(&'a self, text: &'a str) -> impl Iterator<Item = &'a String> + 'a {
+        self.do_not_translate.iter().filter(move |term| contains_term(text, term))
+    }
+
+    /// Instructions for the translation system prompt, limited to the terms that occur in `text`.
+    pub fn prompt_section(&self, language: &str, text: &str) -> Option<String> {
+        let mut section = String::new();
+
+        let entries: Vec<&GlossaryEntry> = self.relevant_entries(language, text).collect();
+        if !entries.is_empty() {
+            section.push_str("Always translate these terms exactly as given:");
+            for entry in entries {
+                section.push_str(&format!("\n- \"{}\" → \"{}\"", entry.source, entry.target));
+            }
+        }
+
+        let terms: Vec<&String> = self.relevant_terms(text).collect();
+        if !terms.is_empty() {
+            if !section.is_empty() {
+                section.push_str("\n\n");
+            }
+            section.push_str("Never translate, inflect or transliterate these names; copy them verbatim:");
+            for term in terms {
+                section.push_str(&format!("\n- {}", term));
+            }
+        }
+
+        (!section.is_empty()).then_some(section)
+    }
+
+    /// Checks that every relevant glossary and do-not-translate term made it into `translation`.
+    pub fn verify(&self, language: &str, source: &str, translation: &str) -> Vec<GlossaryViolation> {
+        let entries = self.relevant_entries(language, source)
+            .filter(|entry| !contains_term(translation, &entry.target))
+            .map(|entry| GlossaryViolation {
+                term: entry.source.clone(),
+                expected: entry.target.clone(),
+            });
+        let terms = self.relevant_terms(source)
+            .filter(|term| !contains_term(translation, term))
+            .map(|term| GlossaryViolation {
+                term: term.clone(),
+                expected: term.clone(),
+            });
+        entries.chain(terms).collect()
+    }
+}
+
+/// Case-insensitive whole-word search, so "Shorty" doesn't match inside "Shortyness".
+/// Scripts written without spaces have no word boundaries, so "Shortyは" and "東京都" still match.
+fn contains_term(text: &str, term: &str) -> bool {
+    let term = term.trim().to_lowercase();
+    let (Some(first), Some(last)) = (term.chars().next(), term.chars().next_back()) else {
+        return false;
+    };
+    let text = text.to_lowercase();
+    // A neighbour only extends the word when it and the term's edge both belong to a spaced script
+    let joins = |edge: char, neighbour: Option<char>| neighbour.is_some_and(|c| is_spaced_word_char(c) && is_spaced_word_char(edge));
+    text.match_indices(&term).any(|(start, _)| {
+        !joins(first, text[..start].chars().next_back()) && !joins(last, text[start + term.len()..].chars().next())
+    })
+}
+
+/// Letters, digits and '_' of scripts that separate words with spaces.
+fn is_spaced_word_char(c: char) -> bool {
+    let unspaced = matches!(c,
+        '\u{0E00}'..='\u{0EFF}'       // Thai, Lao
+        | '\u{1000}'..='\u{109F}'     // Myanmar
+        | '\u{1780}'..='\u{17FF}'     // Khmer
+        | '\u{3040}'..='\u{31FF}'     // Hiragana, Katakana, Bopomofo, CJK strokes
+        | '\u{3400}'..='\u{4DBF}'     // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'     // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'     // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'     // Halfwidth Katakana
+        | '\u{20000}'..='\u{3FFFF}'   // CJK extensions B and later
+    );
+    (c.is_alphanumeric() || c == '_') && !unspaced
+}
+
+fn language_code(name: &str) -> String {
+    LANGUAGE_CODES.iter()
+        .find(|(language, _)| language.eq_ignore_ascii_case(name))
+        .map(|(_, code)| code.to_string())
+        .unwrap_or_else(|| name.to_lowercase())
+}
+
+fn language_name(code: &str) -> String {
+    // "de-DE" and "de" both mean German here
+    let primary = code.split(['-', '_']).next().unwrap_or(code);
+    LANGUAGE_CODES.iter()
+        .find(|(_, language_code)| language_code.eq_ignore_ascii_case(primary))
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| code.to_string())
+}
+
+fn glossary_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(GLOSSARY_FILE))
+}
+
+fn read_glossary(path: &Path) -> Result<Glossary, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse glossary: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Glossary::default()),
+        Err(e) => Err(format!("Failed to read glossary: {}", e)),
+    }
+}
+
+fn write_glossary(path: &Path, glossary: &Glossary) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(glossary)
+        .map_err(|e| format!("Failed to serialize glossary: {}", e))?;
+
+    // Write to a temporary file first so a crash never leaves a truncated glossary
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, contents)
+        .map_err(|e| format!("Failed to write glossary: {}", e))?;
+    std::fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed to replace glossary: {}", e))
+}
+
+/// The stored glossary; an unreadable file is reported and treated as empty so translation keeps working.
+pub fn load(app_handle: &tauri::AppHandle) -> Glossary {
+    let result = glossary_path(app_handle).and_then(|path| read_glossary(&path));
+    result.unwrap_or_else(|e| {
+        println!("⚠️ Glossary unavailable: {}", e);
+        Glossary::default()
+    })
+}
+
+/// CSV with a `source,target,language` header; rows without a target are do-not-translate terms.
+fn parse_csv(contents: &str) -> Result<Glossary, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let headers = reader.headers()
+        .map_err(|e| format!("Failed to read CSV header: {}", e))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|header| header.eq_ignore_ascii_case(name));
+    let source_column = column("source").ok_or("CSV glossary needs a 'source' column")?;
+    let target_column = column("target");
+    let language_column = column("language");
+
+    let mut glossary = Glossary::default();
+    for (line, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Failed to read CSV row {}: {}", line + 2, e))?;
+        let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or("").to_string();
+        let source = field(Some(source_column));
+        let target = field(target_column);
+        let language = field(language_column);
+
+        if source.is_empty() {
+            continue;
+        }
+        if target.is_empty() {
+            glossary.do_not_translate.push(source);
+        } else if language.is_empty() {
+            return Err(format!("CSV row {} has a target but no language", line + 2));
+        } else {
+            glossary.entries.push(GlossaryEntry { source, target, language });
+        }
+    }
+    Ok(glossary)
+}
+
+fn to_csv(glossary: &Glossary) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = |row: [&str; 3]| writer.write_record(row).map_err(|e| format!("Failed to write CSV: {}", e));
+    write(["source", "target", "language"])?;
+    for entry in &glossary.entries {
+        write([&entry.source, &entry.target, &entry.language])?;
+    }
+    for term in &glossary.do_not_translate {
+        write([term, "", ""])?;
+    }
+    let bytes = writer.into_inner().map_err(|e| format!("Failed to write CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+/// TBX: each `termEntry` holds one `langSet` per language. The first (or the one in the
+/// source language) supplies the source term; an entry with no other language is do-not-translate.
+fn parse_tbx(contents: &str, source_language: &str) -> Result<Glossary, String> {
+    let mut reader = quick_xml::Reader::from_str(contents);
+    reader.config_mut().trim_text(true);
+
+    let source_code = language_code(source_language);
+    let mut glossary = Glossary {
+        source_language: source_language.to_string(),
+        ..Glossary::default()
+    };
+    // (language code, term) pairs of the current termEntry
+    let mut terms: Vec<(String, String)> = Vec::new();
+    let mut language = String::new();
+    let mut in_term = false;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) => match element.local_name().as_ref() {
+                b"termEntry" => terms.clear(),
+                b"langSet" => {
+                    language = element.attributes()
+                        .flatten()
+                        .find(|attribute| attribute.key.as_ref() == b"xml:lang")
+                        .and_then(|attribute| attribute.unescape_value().ok())
+                        .map(|value| value.to_string())
+                        .unwrap_or_default();
+                }
+                b"term" => in_term = true,
+                _ => {}
+            },
+            Ok(Event::Text(text)) if in_term => {
+                let term = text.unescape().map_err(|e| format!("Invalid TBX term: {}", e))?;
+                terms.push((language.clone(), term.trim().to_string()));
+            }
+            Ok(Event::End(element)) => match element.local_name().as_ref() {
+                b"term" => in_term = false,
+                b"termEntry" => {
+                    let source_index = terms.iter()
+                        .position(|(code, _)| language_name(code).eq_ignore_ascii_case(source_language) || code.eq_ignore_ascii_case(&source_code))
+                        .unwrap_or(0);
+                    if source_index >= terms.len() {
+                        continue;
+                    }
+                    let (_, source) = terms.remove(source_index);
+                    if terms.is_empty() {
+                        glossary.do_not_translate.push(source);
+                        continue;
+                    }
+                    for (code, target) in terms.drain(..) {
+                        glossary.entries.push(GlossaryEntry {
+                            source: source.clone(),
+                            target,
+                            language: language_name(&code),
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(format!("Invalid TBX at byte {}: {}", reader.error_position(), e)),
+        }
+    }
+    Ok(glossary)
+}
+
+fn to_tbx(glossary: &Glossary) -> String {
+    use quick_xml::escape::escape;
+
+    let source_code = language_code(&glossary.source_language);
+    let lang_set = |code: &str, term: &str| {
+        format!("        <langSet xml:lang=\"{}\"><tig><term>{}</term></tig></langSet>\n", escape(code), escape(term))
+    };
+
+    let mut body = String::new();
+    let mut id = 0;
+    let mut entry = |sets: String| {
+        id += 1;
+        body.push_str(&format!("      <termEntry id=\"t{}\">\n{}      </termEntry>\n", id, sets));
+    };
+
+    // Group targets by source term so one concept keeps all its languages
+    let mut sources: Vec<&str> = Vec::new();
+    for glossary_entry in &glossary.entries {
+        if !sources.contains(&glossary_entry.source.as_str()) {
+            sources.push(&glossary_entry.source);
+        }
+    }
+    for source in sources {
+        let mut sets = lang_set(&source_code, source);
+        for glossary_entry in glossary.entries.iter().filter(|e| e.source == source) {
+            sets.push_str(&lang_set(&language_code(&glossary_entry.language), &glossary_entry.target));
+        }
+        entry(sets);
+    }
+    for term in &glossary.do_not_translate {
+        entry(lang_set(&source_code, term));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tbx type=\"TBX-Basic\" style=\"dca\" xml:lang=\"{}\" xmlns=\"urn:iso:std:iso:30042:ed-2\">\n  <text>\n    <body>\n{}    </body>\n  </text>\n</tbx>\n",
+        escape(&source_code),
+        body
+    )
+}
+
+#[tauri::command]
+pub async fn get_glossary(app_handle: tauri::AppHandle) -> Result<Glossary, String> {
+    read_glossary(&glossary_path(&app_handle)?)
+}
+
+#[tauri::command]
+pub async fn save_glossary(app_handle: tauri::AppHandle, glossary: Glossary) -> Result<Glossary, String> {
+    glossary.validate()?;
+    let path = glossary_path(&app_handle)?;
+    let _guard = GLOSSARY_LOCK.lock().map_err(|_| "Failed to acquire glossary lock")?;
+    write_glossary(&path, &glossary)?;
+    println!("📖 Saved glossary ({} entries, {} do-not-translate terms)", glossary.entries.len(), glossary.do_not_translate.len());
+    Ok(glossary)
+}
+
+/// Imports a CSV or TBX file (format taken from the extension unless given). Terms are merged
+/// into the existing glossary, replacing entries with the same source term and language, unless `replace` is set.
+#[tauri::command]
+pub async fn import_glossary(
+    app_handle: tauri::AppHandle,
+    path: String,
+    format: Option<GlossaryFormat>,
+    replace: Option<bool>,
+) -> Result<Glossary, String> {
+    let source_path = PathBuf::from(&path);
+    let format = match format {
+        Some(format) => format,
+        None => GlossaryFormat::from_path(&source_path)?,
+    };
+    let contents = std::fs::read_to_string(&source_path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let glossary_file = glossary_path(&app_handle)?;
+    let _guard = GLOSSARY_LOCK.lock().map_err(|_| "Failed to acquire glossary lock")?;
+    let mut glossary = if replace.unwrap_or(false) {
+        Glossary::default()
+    } else {
+        read_glossary(&glossary_file)?
+    };
+
+    let imported = match format {
+        GlossaryFormat::Csv => parse_csv(&contents)?,
+        GlossaryFormat::Tbx => parse_tbx(&contents, &glossary.source_language)?,
+    };
+    imported.validate()?;
+
+    for entry in &imported.entries {
+        glossary.entries.retain(|existing| {
+            !(existing.source.eq_ignore_ascii_case(&entry.source) && existing.language.eq_ignore_ascii_case(&entry.language))
+        });
+    }
+    glossary.entries.extend(imported.entries.iter().cloned());
+    for term in &imported.do_not_translate {
+        if !glossary.do_not_translate.contains(term) {
+            glossary.do_not_translate.push(term.clone());
+        }
+    }
+
+    write_glossary(&glossary_file, &glossary)?;
+    println!("📥 Imported {} glossary entries and {} do-not-translate terms from {}", imported.entries.len(), imported.do_not_translate.len(), path);
+    Ok(glossary)
+}
+
+#[tauri::command]
+pub async fn export_glossary(app_handle: tauri::AppHandle, path: String, format: Option<GlossaryFormat>) -> Result<(), String> {
+    let target_path = PathBuf::from(&path);
+    let format = match format {
+        Some(format) => format,
+        None => GlossaryFormat::from_path(&target_path)?,
+    };
+    let glossary = read_glossary(&glossary_path(&app_handle)?)?;
+
+    let contents = match format {
+        GlossaryFormat::Csv => to_csv(&glossary)?,
+        GlossaryFormat::Tbx => to_tbx(&glossary),
+    };
+    std::fs::write(&target_path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    println!("📤 Exported glossary to {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary() -> Glossary {
+        Glossary {
+            source_language: "English".to_string(),
+            entries: vec![
+                GlossaryEntry { source: "invoice".to_string(), target: "Rechnung".to_string(), language: "German".to_string() },
+                GlossaryEntry { source: "invoice".to_string(), target: "facture".to_string(), language: "French".to_string() },
+                GlossaryEntry { source: "due date".to_string(), target: "Fälligkeitsdatum".to_string(), language: "German".to_string() },
+            ],
+            do_not_translate: vec!["Go".to_string(), "Shorty, Inc.".to_string()],
+        }
+    }
+
+    #[test]
+    fn csv_round_trips() {
+        let csv = "source,target,language\n\
+                   invoice,Rechnung,German\n\
+                   invoice,facture,French\n\
+                   due date,Fälligkeitsdatum,German\n\
+                   Go,,\n\
+                   \"Shorty, Inc.\",,\n";
+        let parsed = parse_csv(csv).unwrap();
+        assert_eq!(parsed, glossary());
+        assert_eq!(parse_csv(&to_csv(&parsed).unwrap()).unwrap(), parsed);
+    }
+
+    #[test]
+    fn csv_columns_are_matched_by_name() {
+        let parsed = parse_csv("Language, Source, Target\nGerman, invoice, Rechnung\n, Go,\n").unwrap();
+        assert_eq!(parsed.entries, vec![GlossaryEntry {
+            source: "invoice".to_string(),
+            target: "Rechnung".to_string(),
+            language: "German".to_string(),
+        }]);
+        assert_eq!(parsed.do_not_translate, vec!["Go".to_string()]);
+    }
+
+    #[test]
+    fn csv_rejects_targets_without_a_language() {
+        assert!(parse_csv("source,target\ninvoice,Rechnung\n").is_err());
+        assert!(parse_csv("term,target\ninvoice,Rechnung\n").is_err());
+    }
+
+    #[test]
+    fn tbx_round_trips() {
+        let tbx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tbx type="TBX-Basic" xml:lang="en" xmlns="urn:iso:std:iso:30042:ed-2">
+  <text>
+    <body>
+      <termEntry id="c1">
+        <langSet xml:lang="de-DE"><tig><term>Rechnung</term></tig></langSet>
+        <langSet xml:lang="en"><tig><term>invoice</term></tig></langSet>
+        <langSet xml:lang="fr"><tig><term>facture</term></tig></langSet>
+      </termEntry>
+      <termEntry id="c2">
+        <langSet xml:lang="en"><tig><term>due date</term></tig></langSet>
+        <langSet xml:lang="de"><tig><term>Fälligkeitsdatum</term></tig></langSet>
+      </termEntry>
+      <termEntry id="c3">
+        <langSet xml:lang="en"><tig><term>Go</term></tig></langSet>
+      </termEntry>
+      <termEntry id="c4">
+        <langSet xml:lang="en"><tig><term>Shorty, Inc.</term></tig></langSet>
+      </termEntry>
+    </body>
+  </text>
+</tbx>"#;
+        let parsed = parse_tbx(tbx, "English").unwrap();
+        assert_eq!(parsed, glossary());
+        assert_eq!(parse_tbx(&to_tbx(&parsed), "English").unwrap(), parsed);
+    }
+
+    #[test]
+    fn tbx_escapes_markup_in_terms() {
+        let glossary = Glossary {
+            do_not_translate: vec!["R&D <Lab>".to_string()],
+            ..Glossary::default()
+        };
+        let exported = to_tbx(&glossary);
+        assert!(exported.contains("R&amp;D &lt;Lab&gt;"));
+        assert_eq!(parse_tbx(&exported, "English").unwrap(), glossary);
+    }
+
+    #[test]
+    fn tbx_reports_malformed_files() {
+        assert!(parse_tbx("<tbx><text><body><termEntry></body></tbx>", "English").is_err());
+    }
+
+    #[test]
+    fn verify_accepts_faithful_translations() {
+        let violations = glossary().verify(
+            "German",
+            "Pay the invoice by the due date. Go is fine.",
+            "Zahlen Sie die Rechnung bis zum Fälligkeitsdatum. Go ist in Ordnung.",
+        );
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn verify_reports_missing_glossary_targets() {
+        let violations = glossary().verify("German", "Pay the invoice.", "Zahlen Sie die Faktura.");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].term, "invoice");
+        assert_eq!(violations[0].expected, "Rechnung");
+    }
+
+    #[test]
+    fn verify_only_checks_the_target_language() {
+        let violations = glossary().verify("French", "Pay the invoice.", "Payez la facture.");
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn terms_match_next_to_scripts_without_spaces() {
+        assert!(contains_term("Shortyは便利です。", "Shorty"));
+        assert!(contains_term("我用Shorty的翻译", "shorty"));
+        assert!(contains_term("東京都に住んでいます", "東京"));
+        assert!(contains_term("ใช้Shortyแปล", "Shorty"));
+        // Spaced scripts still need a boundary, whatever their alphabet
+        assert!(!contains_term("Shortyness", "Shorty"));
+        assert!(!contains_term("Шортиан", "Шорти"));
+        assert!(!contains_term("Shorty2", "Shorty"));
+    }
+
+    #[test]
+    fn verify_accepts_terms_inside_japanese_and_chinese_text() {
+        let glossary = Glossary {
+            entries: vec![GlossaryEntry { source: "invoice".to_string(), target: "請求書".to_string(), language: "Japanese".to_string() }],
+            do_not_translate: vec!["Shorty".to_string()],
+            ..Glossary::default()
+        };
+        assert!(glossary.verify("Japanese", "Send the invoice from Shorty.", "Shortyから請求書を送ってください。").is_empty());
+        assert!(glossary.verify("Chinese", "Shorty is fast.", "Shorty的速度很快。").is_empty());
+
+        let violations = glossary.verify("Japanese", "Send the invoice from Shorty.", "ショーティから送り状を送ってください。");
+        assert_eq!(violations.len(), 2);
+    }
+
+    #[test]
+    fn verify_matches_do_not_translate_terms_as_whole_words() {
+        // "Go" inside "Google" doesn't count as keeping the term
+        let violations = glossary().verify("German", "Go is fast.", "Google ist schnell.");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].term, "Go");
+
+        // Terms that don't occur in the source are not expected in the translation
+        assert!(glossary().verify("German", "Google is big.", "Google ist groß.").is_empty());
+
+        // Matching ignores case, like the glossary target check
+        assert!(glossary().verify("German", "Ask shorty, inc. first.", "Fragen Sie zuerst SHORTY, INC.").is_empty());
+    }
+}
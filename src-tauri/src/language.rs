@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Translates into `alternate_language` when the text is already in the requested target language,
+/// e.g. "German text goes to English, everything else to German".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmartTargetRule {
+    pub enabled: bool,
+    pub alternate_language: String,
+}
+
+impl Default for SmartTargetRule {
+    fn default() -> Self {
+        SmartTargetRule {
+            enabled: true,
+            alternate_language: "English".to_string(),
+        }
+    }
+}
+
+impl SmartTargetRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.alternate_language.trim().is_empty() {
+            return Err("smart_target.alternate_language must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Result of local language detection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// English name, matching the names used for target languages (e.g. "German")
+    pub name: String,
+    /// ISO 639-3 code
+    pub code: String,
+    pub confidence: f64,
+    /// Whether the detection is certain enough to act on
+    pub reliable: bool,
+}
+
+impl DetectedLanguage {
+    pub fn is(&self, language: &str) -> bool {
+        self.name.eq_ignore_ascii_case(language.trim())
+    }
+}
+
+/// Detects the language of `text` locally, without an API call.
+pub fn detect(text: &str) -> Option<DetectedLanguage> {
+    let info = whatlang::detect(text)?;
+    let name = match info.lang() {
+        // The translator offers "Chinese" rather than the spoken variety
+        whatlang::Lang::Cmn => "Chinese",
+        lang => lang.eng_name(),
+    };
+    Some(DetectedLanguage {
+        name: name.to_string(),
+        code: info.lang().code().to_string(),
+        confidence: info.confidence(),
+        reliable: info.is_reliable(),
+    })
+}
+
+/// Picks the language to translate into. Only a reliable detection can redirect the translation.
+pub fn choose_target(requested: &str, source: Option<&DetectedLanguage>, rule: &SmartTargetRule) -> String {
+    match source {
+        Some(source) if rule.enabled && source.reliable && source.is(requested) && !source.is(&rule.alternate_language) => {
+            println!("🌍 Text is already in {}, translating to {} instead", source.name, rule.alternate_language);
+            rule.alternate_language.clone()
+        }
+        _ => requested.to_string(),
+    }
+}
@@ -1,15 +1,58 @@
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, Listener};
-use std::env;
+use tauri::{Emitter, Listener, Manager};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 
+mod alternatives;
+mod capture;
+mod credentials;
+mod custom_agents;
+mod diarization;
+mod dsp;
+mod explain;
+mod glossary;
+mod language;
+mod markup;
+mod meeting_notes;
+mod metering;
+mod pipeline;
+mod prompts;
+#[cfg(target_os = "linux")]
+mod pulse;
+mod quality;
+mod recordings;
+mod rewrite;
+mod settings;
+mod style;
+mod summary;
+mod translation_memory;
+
+use capture::{RecordingInterrupted, RecordingRecovered, SampleConverter};
+use diarization::{AudioSource, SpeakerSegment, TranscriptSegment};
+use metering::{AudioLevel, LevelMeter, SilenceDetector, SilenceWarning};
+use recordings::SessionInfo;
+
+const LEVEL_REPORT_INTERVAL_MS: u64 = 250;
+const STREAM_STALL_SECONDS: u64 = 3;
+const RECOVERY_RETRY_SECONDS: u64 = 2;
+const DEVICE_POLL_SECONDS: u64 = 3;
+const TRANSCRIPTION_MODEL: &str = "whisper-1";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranslationRequest {
     pub text: String,
     pub target_language: String,
-    pub api_key: String,
+    /// Named credential to use instead of the active one; keys themselves never come from the frontend
+    pub credential: Option<String>,
+    /// Markdown, HTML or code are translated without touching their markup; detected when omitted
+    pub format: Option<markup::DocumentFormat>,
+    /// Formality, tone and locale; unset fields use what was last chosen for the target language
+    #[serde(flatten)]
+    pub style: style::TranslationStyle,
+    /// Back-translate the result and flag divergent sentences; defaults to `quality_check.enabled`
+    pub verify: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,15 +60,68 @@ pub struct TranslationResponse {
     pub success: bool,
     pub translated_text: Option<String>,
     pub error: Option<String>,
+    pub metadata: Option<ResponseMetadata>,
+    /// Glossary and do-not-translate terms the translation did not keep
+    #[serde(default)]
+    pub glossary_violations: Vec<glossary::GlossaryViolation>,
+    /// Language detected in the text, if any
+    pub source_language: Option<language::DetectedLanguage>,
+    /// Language actually translated into, after the smart target rule
+    pub target_language: String,
+    /// Similarity of the translation memory match that was used; 1.0 means the translation came from memory
+    #[serde(default)]
+    pub memory_score: Option<f64>,
+    /// Style the translation was made in
+    #[serde(default)]
+    pub style: style::TranslationStyle,
+    /// Back-translation check, when one was requested and succeeded
+    #[serde(default)]
+    pub quality: Option<quality::QualityReport>,
+}
+
+/// Which credential and model served a request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseMetadata {
+    pub credential: String,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CaptureFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// A stretch of the session that was not captured. `at_seconds` is the position in the
+/// recorded audio where the gap sits, `duration_seconds` how long the pause lasted.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RecordingPause {
+    pub at_seconds: f32,
+    pub duration_seconds: f32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AudioRecordingResult {
     pub success: bool,
     pub audio_data: Option<Vec<f32>>,
+    pub format: Option<CaptureFormat>,
+    pub microphone_data: Option<Vec<f32>>,
+    pub microphone_format: Option<CaptureFormat>,
+    pub pauses: Vec<RecordingPause>,
+    pub recording_id: Option<String>,
     pub error: Option<String>,
 }
 
+/// The captured tracks of one recording session.
+#[derive(Debug, Clone)]
+struct RecordedTracks {
+    audio_data: Vec<f32>,
+    format: CaptureFormat,
+    microphone_data: Option<Vec<f32>>,
+    microphone_format: Option<CaptureFormat>,
+    pauses: Vec<RecordingPause>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranscriptionResult {
     pub success: bool,
@@ -33,42 +129,288 @@ pub struct TranscriptionResult {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiarizedTranscriptionRequest {
+    pub audio_data: Vec<f32>,
+    pub format: CaptureFormat,
+    pub microphone_data: Option<Vec<f32>>,
+    pub microphone_format: Option<CaptureFormat>,
+    pub pauses: Option<Vec<RecordingPause>>,
+    pub max_speakers: Option<usize>,
+    pub recording_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiarizedTranscriptionResult {
+    pub success: bool,
+    pub segments: Option<Vec<SpeakerSegment>>,
+    pub transcription: Option<String>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SummarizeRequest {
     pub text: String,
-    pub api_key: String,
+    pub credential: Option<String>,
+    /// Mode, word limit and output language; the saved summary settings when omitted
+    pub options: Option<summary::SummaryOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummarizeResponse {
+    pub summary: String,
+    pub mode: summary::SummaryMode,
+    pub metadata: ResponseMetadata,
 }
 
 // Global audio recording state
 lazy_static::lazy_static! {
     static ref AUDIO_DATA: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(Vec::new()));
+    static ref MIC_DATA: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(Vec::new()));
     static ref IS_RECORDING: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    static ref IS_PAUSED: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    static ref PAUSES: Mutex<Vec<RecordingPause>> = Mutex::new(Vec::new());
+    static ref PAUSE_STARTED: Mutex<Option<std::time::Instant>> = Mutex::new(None);
+    static ref SESSION_INFO: Mutex<SessionInfo> = Mutex::new(SessionInfo::default());
+    static ref AUDIO_FORMAT: Mutex<Option<CaptureFormat>> = Mutex::new(None);
+    static ref MIC_FORMAT: Mutex<Option<CaptureFormat>> = Mutex::new(None);
+}
+
+/// Detects the source language and applies the smart target rule to the requested language.
+fn detect_target(settings: &settings::Settings, request: TranslationRequest) -> (TranslationRequest, Option<language::DetectedLanguage>) {
+    let source_language = language::detect(&request.text);
+    if let Some(source) = &source_language {
+        println!("🔎 Detected {} ({:.0}% confidence)", source.name, source.confidence * 100.0);
+    }
+    let target_language = language::choose_target(&request.target_language, source_language.as_ref(), &settings.smart_target);
+    (TranslationRequest { target_language, ..request }, source_language)
+}
+
+/// Fills unset style fields from what was last used for the target language and remembers explicit choices.
+fn resolve_style(app_handle: &tauri::AppHandle, settings: &settings::Settings, request: &TranslationRequest) -> style::TranslationStyle {
+    let key = style::language_key(&request.target_language);
+    let remembered = settings.translation_styles.get(&key).cloned().unwrap_or_default();
+    let style = request.style.or(&remembered);
+    if !request.style.is_empty() && style != remembered {
+        let result = settings::modify(app_handle, |settings| {
+            settings.translation_styles.insert(key, style.clone());
+        });
+        if let Err(e) = result {
+            println!("⚠️ Failed to remember the translation style: {}", e);
+        }
+    }
+    style
+}
+
+/// Renders the translate template and appends the style instructions, the glossary terms that
+/// occur in the text and, for a fuzzy translation memory match, the earlier translation as a reference.
+fn translation_prompt(
+    settings: &settings::Settings,
+    glossary: &glossary::Glossary,
+    request: &TranslationRequest,
+    style: &style::TranslationStyle,
+    remembered: Option<&translation_memory::MemoryMatch>,
+) -> prompts::RenderedPrompt {
+    let mut prompt = prompts::render_for(settings, prompts::PromptKind::Translate, HashMap::from([
+        ("target_language".to_string(), request.target_language.clone()),
+        ("text".to_string(), request.text.clone()),
+        ("tone".to_string(), style.tone.map_or(prompts::DEFAULT_TONE, style::Tone::name).to_string()),
+    ]));
+    if let Some(section) = style.prompt_section(&request.target_language) {
+        prompt.system.push_str("\n\n");
+        prompt.system.push_str(&section);
+    }
+    if let Some(section) = glossary.prompt_section(&request.target_language, &request.text) {
+        prompt.system.push_str("\n\n");
+        prompt.system.push_str(&section);
+    }
+    if let Some(remembered) = remembered {
+        prompt.system.push_str("\n\n");
+        prompt.system.push_str(&translation_memory::reference_section(remembered));
+    }
+    prompt
+}
+
+/// Looks the text up in the translation memory; failures only cost the cache, never the translation.
+async fn recall(app_handle: &tauri::AppHandle, settings: &settings::Settings, text: &str, key: &translation_memory::MemoryKey) -> Option<translation_memory::MemoryMatch> {
+    if !settings.translation_memory.enabled {
+        return None;
+    }
+    let memory = translation_memory::state(app_handle).ok()?;
+    match memory.lookup(text, key, settings.translation_memory.fuzzy_threshold).await {
+        Ok(Some(remembered)) => {
+            println!("🧠 Translation memory match ({:.0}%) for {}", remembered.score * 100.0, key.target_language);
+            Some(remembered)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            println!("⚠️ {}", e);
+            None
+        }
+    }
+}
+
+/// Stores a finished translation unless it broke the glossary.
+async fn remember(app_handle: &tauri::AppHandle, settings: &settings::Settings, text: &str, translation: &str, key: &translation_memory::MemoryKey, violations: &[glossary::GlossaryViolation]) {
+    if !settings.translation_memory.enabled || !violations.is_empty() || translation.trim().is_empty() {
+        return;
+    }
+    let Ok(memory) = translation_memory::state(app_handle) else {
+        return;
+    };
+    if let Err(e) = memory.store(text, translation, key, settings.translation_memory.max_entries).await {
+        println!("⚠️ {}", e);
+    }
+}
+
+/// Verifies the glossary against a finished translation and logs what the model missed.
+fn check_glossary(glossary: &glossary::Glossary, request: &TranslationRequest, translation: &str) -> Vec<glossary::GlossaryViolation> {
+    let violations = glossary.verify(&request.target_language, &request.text, translation);
+    for violation in &violations {
+        println!("⚠️ Glossary term \"{}\" not rendered as \"{}\"", violation.term, violation.expected);
+    }
+    violations
+}
+
+/// Back-translates a finished translation when the request, or else the settings, ask for it.
+/// Failures are logged rather than failing a translation that itself succeeded.
+async fn verify_translation(
+    api_key: &str,
+    settings: &settings::Settings,
+    request: &TranslationRequest,
+    source_language: Option<&language::DetectedLanguage>,
+    translation: &str,
+) -> Option<quality::QualityReport> {
+    if !request.verify.unwrap_or(settings.quality_check.enabled) {
+        return None;
+    }
+    let Some(source_language) = source_language else {
+        println!("⚠️ Skipping back-translation check: source language could not be detected");
+        return None;
+    };
+    match quality::check(api_key, &settings.quality_check, &settings.model, &request.text, translation, &source_language.name).await {
+        Ok(report) => Some(report),
+        Err(e) => {
+            println!("⚠️ {}", e);
+            None
+        }
+    }
+}
+
+/// Runs the back-translation check for a tab of the translation window and emits the report.
+async fn emit_quality_report(
+    window: &tauri::WebviewWindow,
+    tab: &str,
+    api_key: &str,
+    settings: &settings::Settings,
+    request: &TranslationRequest,
+    source_language: Option<&language::DetectedLanguage>,
+    translation: &str,
+) {
+    if let Some(report) = verify_translation(api_key, settings, request, source_language, translation).await {
+        let _ = window.emit("translation-quality", &TabEvent { tab, data: &report });
+    }
 }
 
 #[tauri::command]
-async fn translate_text(request: TranslationRequest) -> Result<TranslationResponse, String> {
+async fn translate_text(app_handle: tauri::AppHandle, request: TranslationRequest) -> Result<TranslationResponse, String> {
+    request.style.validate()?;
+    let credential = credentials::resolve(&app_handle, request.credential.as_deref())?;
+    let settings = settings::current(&app_handle);
+    let metadata = ResponseMetadata {
+        credential: credential.name.clone(),
+        model: settings.model.clone(),
+    };
+    let (request, source_language) = detect_target(&settings, request);
+    let style = resolve_style(&app_handle, &settings, &request);
+    let glossary = glossary::load(&app_handle);
+    let memory_key = translation_memory::MemoryKey {
+        target_language: request.target_language.clone(),
+        style: style.fingerprint(),
+        model: settings.model.clone(),
+        glossary_version: glossary.version(),
+    };
+
+    // An exact match answers without an API call
+    let remembered = recall(&app_handle, &settings, &request.text, &memory_key).await;
+    if let Some(exact) = remembered.as_ref().filter(|remembered| remembered.score >= 1.0) {
+        let quality = verify_translation(&credential.api_key, &settings, &request, source_language.as_ref(), &exact.entry.translation).await;
+        return Ok(TranslationResponse {
+            success: true,
+            translated_text: Some(exact.entry.translation.clone()),
+            error: None,
+            metadata: Some(metadata),
+            glossary_violations: Vec::new(),
+            source_language,
+            target_language: request.target_language.clone(),
+            memory_score: Some(exact.score),
+            style: style.clone(),
+            quality,
+        });
+    }
+    let prompt = translation_prompt(&settings, &glossary, &request, &style, remembered.as_ref());
+
+    // Markup-heavy text is translated segment by segment so its structure survives
+    let format = request.format.unwrap_or_else(|| markup::detect_format(&request.text));
+    if format != markup::DocumentFormat::Plain {
+        let document = markup::Document::parse(&request.text, format);
+        println!("🧩 Translating {:?} document ({} segments) to {}", format, document.segments.len(), request.target_language);
+        return Ok(match markup::translate_document(&credential.api_key, &settings, &prompt.system, &document, format).await {
+            Ok(translation) => {
+                let glossary_violations = check_glossary(&glossary, &request, &translation.text);
+                if translation.untranslated_segments == 0 {
+                    remember(&app_handle, &settings, &request.text, &translation.text, &memory_key, &glossary_violations).await;
+                }
+                let quality = verify_translation(&credential.api_key, &settings, &request, source_language.as_ref(), &translation.text).await;
+                TranslationResponse {
+                    success: true,
+                    translated_text: Some(translation.text),
+                    error: None,
+                    metadata: Some(metadata),
+                    glossary_violations,
+                    source_language,
+                    target_language: request.target_language.clone(),
+                    memory_score: remembered.as_ref().map(|remembered| remembered.score),
+                    style: style.clone(),
+                    quality,
+                }
+            }
+            Err(e) => TranslationResponse {
+                success: false,
+                translated_text: None,
+                error: Some(e),
+                metadata: Some(metadata),
+                glossary_violations: Vec::new(),
+                source_language,
+                target_language: request.target_language.clone(),
+                memory_score: None,
+                style: style.clone(),
+                quality: None,
+            },
+        });
+    }
     let client = reqwest::Client::new();
     
     let payload = serde_json::json!({
-        "model": "gpt-4o-mini",
+        "model": settings.model,
         "messages": [
             {
                 "role": "system",
-                "content": format!("You are a professional translator. Translate the given text to {}. Only respond with the translation, no explanations or additional text.", request.target_language)
+                "content": prompt.system
             },
             {
                 "role": "user",
-                "content": request.text
+                "content": prompt.user
             }
         ],
-        "temperature": 0.3,
-        "max_tokens": 1000,
-        "stream": true
+        "temperature": settings.temperature,
+        "max_tokens": 1000
     });
 
+    // Not streamed: the whole response is parsed as one JSON body below
     match client
         .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", request.api_key))
+        .header("Authorization", format!("Bearer {}", credential.api_key))
         .header("Content-Type", "application/json")
         .json(&payload)
         .send()
@@ -79,16 +421,33 @@ async fn translate_text(request: TranslationRequest) -> Result<TranslationRespon
                 match response.json::<serde_json::Value>().await {
                     Ok(data) => {
                         if let Some(content) = data["choices"][0]["message"]["content"].as_str() {
+                            let glossary_violations = check_glossary(&glossary, &request, content);
+                            remember(&app_handle, &settings, &request.text, content, &memory_key, &glossary_violations).await;
+                            let quality = verify_translation(&credential.api_key, &settings, &request, source_language.as_ref(), content).await;
                             Ok(TranslationResponse {
                                 success: true,
                                 translated_text: Some(content.to_string()),
                                 error: None,
+                                metadata: Some(metadata),
+                                glossary_violations,
+                                source_language,
+                                target_language: request.target_language.clone(),
+                                memory_score: remembered.as_ref().map(|remembered| remembered.score),
+                                style: style.clone(),
+                                quality,
                             })
                         } else {
                             Ok(TranslationResponse {
                                 success: false,
                                 translated_text: None,
                                 error: Some("No translation content in response".to_string()),
+                                metadata: Some(metadata),
+                                glossary_violations: Vec::new(),
+                                source_language,
+                                target_language: request.target_language.clone(),
+                                memory_score: None,
+                                style: style.clone(),
+                                quality: None,
                             })
                         }
                     }
@@ -96,6 +455,13 @@ async fn translate_text(request: TranslationRequest) -> Result<TranslationRespon
                         success: false,
                         translated_text: None,
                         error: Some(format!("Failed to parse response: {}", e)),
+                        metadata: Some(metadata),
+                        glossary_violations: Vec::new(),
+                        source_language,
+                        target_language: request.target_language.clone(),
+                        memory_score: None,
+                        style: style.clone(),
+                        quality: None,
                     }),
                 }
             } else {
@@ -103,6 +469,13 @@ async fn translate_text(request: TranslationRequest) -> Result<TranslationRespon
                     success: false,
                     translated_text: None,
                     error: Some(format!("HTTP error: {}", response.status())),
+                    metadata: Some(metadata),
+                    glossary_violations: Vec::new(),
+                    source_language,
+                    target_language: request.target_language.clone(),
+                    memory_score: None,
+                    style: style.clone(),
+                    quality: None,
                 })
             }
         }
@@ -110,6 +483,13 @@ async fn translate_text(request: TranslationRequest) -> Result<TranslationRespon
             success: false,
             translated_text: None,
             error: Some(format!("Request failed: {}", e)),
+            metadata: Some(metadata),
+            glossary_violations: Vec::new(),
+            source_language,
+            target_language: request.target_language.clone(),
+            memory_score: None,
+            style: style.clone(),
+            quality: None,
         }),
     }
 }
@@ -121,7 +501,25 @@ async fn get_clipboard_text() -> Result<String, String> {
 }
 
 #[tauri::command]
-async fn show_translation_window(app_handle: tauri::AppHandle, text: String, target_language: String) -> Result<(), String> {
+async fn show_translation_window(
+    app_handle: tauri::AppHandle,
+    text: String,
+    target_language: String,
+    target_languages: Option<Vec<String>>,
+    alternatives: Option<u32>,
+) -> Result<(), String> {
+    // One tab per distinct target, in the order given
+    let mut targets: Vec<String> = Vec::new();
+    for target in target_languages.unwrap_or_else(|| vec![target_language]) {
+        let target = target.trim().to_string();
+        if !target.is_empty() && !targets.iter().any(|existing| existing.eq_ignore_ascii_case(&target)) {
+            targets.push(target);
+        }
+    }
+    if targets.is_empty() {
+        return Err("No target language given".to_string());
+    }
+
     let window = tauri::WebviewWindowBuilder::new(
         &app_handle,
         "translation",
@@ -137,49 +535,123 @@ async fn show_translation_window(app_handle: tauri::AppHandle, text: String, tar
     // Wait a moment for the window to load, then send the text
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     
-    // Send the clipboard text and target language to the window
+    // Send the clipboard text and the tabs to the window; the languages follow once detected
     let _ = window.emit("clipboard-text", &text);
-    let _ = window.emit("target-language", &target_language);
-    
-    // Start the translation with the WebviewWindow
-    let _ = stream_translate_webview(TranslationRequest {
-        text,
-        target_language,
-        api_key: "dummy".to_string()
-    }, window).await;
+    let _ = window.emit("translation-tabs", &targets);
+    println!("🌍 Translating into {} language(s): {}", targets.len(), targets.join(", "));
+    // Asking for a single alternative is the same as the normal translation
+    let alternatives = alternatives.map(|count| count.min(alternatives::MAX_ALTERNATIVES)).filter(|count| *count > 1);
+
+    // Every target streams concurrently into its own tab
+    let mut translations = tokio::task::JoinSet::new();
+    for target in targets {
+        let request = TranslationRequest {
+            text: text.clone(),
+            target_language: target.clone(),
+            credential: None,
+            format: None,
+            style: style::TranslationStyle::default(),
+            verify: None,
+        };
+        let window = window.clone();
+        translations.spawn(async move {
+            let result = match alternatives {
+                Some(count) => stream_alternatives_webview(request, target.clone(), count, window.clone()).await,
+                None => stream_translate_webview(request, target.clone(), window.clone()).await,
+            };
+            if let Err(e) = result {
+                println!("❌ Translation to {} failed: {}", target, e);
+                let _ = window.emit("translation-error", &TabEvent { tab: &target, data: e });
+            }
+        });
+    }
+    while translations.join_next().await.is_some() {}
     
     Ok(())
 }
 
-async fn stream_translate_webview(request: TranslationRequest, window: tauri::WebviewWindow) -> Result<(), String> {
+/// Event payload for one tab of the translation window; `tab` is the requested target language.
+#[derive(Debug, Clone, Serialize)]
+struct TabEvent<'a, T: Serialize> {
+    tab: &'a str,
+    data: T,
+}
+
+async fn stream_translate_webview(request: TranslationRequest, tab: String, window: tauri::WebviewWindow) -> Result<(), String> {
     use tokio_stream::StreamExt;
     
-    // Read API key from environment variable
-    let api_key = env::var("OPENAI_API_KEY")
-        .map_err(|_| "OPENAI_API_KEY environment variable not found. Please check your .env file.".to_string())?;
-    
+    let credential = credentials::resolve(window.app_handle(), request.credential.as_deref())?;
+    let settings = settings::current(window.app_handle());
+    let _ = window.emit("translation-metadata", &ResponseMetadata {
+        credential: credential.name.clone(),
+        model: settings.model.clone(),
+    });
+    let (request, source_language) = detect_target(&settings, request);
+    if let Some(source_language) = &source_language {
+        let _ = window.emit("source-language", source_language);
+    }
+    let _ = window.emit("target-language", &TabEvent { tab: &tab, data: &request.target_language });
+    let style = resolve_style(window.app_handle(), &settings, &request);
+    let glossary = glossary::load(window.app_handle());
+    let memory_key = translation_memory::MemoryKey {
+        target_language: request.target_language.clone(),
+        style: style.fingerprint(),
+        model: settings.model.clone(),
+        glossary_version: glossary.version(),
+    };
+
+    let remembered = recall(window.app_handle(), &settings, &request.text, &memory_key).await;
+    if let Some(remembered) = &remembered {
+        let _ = window.emit("translation-memory-match", &TabEvent { tab: &tab, data: remembered.score });
+        // An exact match is shown right away instead of calling the API
+        if remembered.score >= 1.0 {
+            let _ = window.emit("translation-complete", &TabEvent { tab: &tab, data: &remembered.entry.translation });
+            emit_quality_report(&window, &tab, &credential.api_key, &settings, &request, source_language.as_ref(), &remembered.entry.translation).await;
+            return Ok(());
+        }
+    }
+    let prompt = translation_prompt(&settings, &glossary, &request, &style, remembered.as_ref());
+
+    // Structured documents can't be streamed piecewise; the window gets the reassembled result
+    let format = request.format.unwrap_or_else(|| markup::detect_format(&request.text));
+    if format != markup::DocumentFormat::Plain {
+        let document = markup::Document::parse(&request.text, format);
+        println!("🧩 Translating {:?} document ({} segments) to {}", format, document.segments.len(), request.target_language);
+        let translation = markup::translate_document(&credential.api_key, &settings, &prompt.system, &document, format).await?;
+        let _ = window.emit("translation-complete", &TabEvent { tab: &tab, data: &translation.text });
+
+        let violations = check_glossary(&glossary, &request, &translation.text);
+        if translation.untranslated_segments == 0 {
+            remember(window.app_handle(), &settings, &request.text, &translation.text, &memory_key, &violations).await;
+        }
+        if !violations.is_empty() {
+            let _ = window.emit("translation-glossary-violations", &TabEvent { tab: &tab, data: &violations });
+        }
+        emit_quality_report(&window, &tab, &credential.api_key, &settings, &request, source_language.as_ref(), &translation.text).await;
+        return Ok(());
+    }
     let client = reqwest::Client::new();
     
     let payload = serde_json::json!({
-        "model": "gpt-4o-mini",
+        "model": settings.model,
         "messages": [
             {
                 "role": "system",
-                "content": format!("You are a professional translator. Translate the given text to {}. Only respond with the translation, no explanations or additional text.", request.target_language)
+                "content": prompt.system
             },
             {
                 "role": "user",
-                "content": request.text
+                "content": prompt.user
             }
         ],
-        "temperature": 0.3,
+        "temperature": settings.temperature,
         "max_tokens": 1000,
         "stream": true
     });
 
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Authorization", format!("Bearer {}", credential.api_key))
         .header("Content-Type", "application/json")
         .json(&payload)
         .send()
@@ -212,7 +684,7 @@ async fn stream_translate_webview(request: TranslationRequest, window: tauri::We
                         buffer.push_str(content);
                         
                         // Emit the chunk to the frontend
-                        let _ = window.emit("translation-chunk", content);
+                        let _ = window.emit("translation-chunk", &TabEvent { tab: &tab, data: content });
                     }
                 }
             }
@@ -220,11 +692,54 @@ async fn stream_translate_webview(request: TranslationRequest, window: tauri::We
     }
 
     // Emit completion
-    let _ = window.emit("translation-complete", &buffer);
+    let _ = window.emit("translation-complete", &TabEvent { tab: &tab, data: &buffer });
+
+    let violations = check_glossary(&glossary, &request, &buffer);
+    remember(window.app_handle(), &settings, &request.text, &buffer, &memory_key, &violations).await;
+    if !violations.is_empty() {
+        let _ = window.emit("translation-glossary-violations", &TabEvent { tab: &tab, data: &violations });
+    }
+    emit_quality_report(&window, &tab, &credential.api_key, &settings, &request, source_language.as_ref(), &buffer).await;
     
     Ok(())
 }
 
+/// Streams several candidate translations into one tab; the window lets the user pick one.
+/// Translation memory is bypassed since reusing one earlier translation defeats the purpose.
+async fn stream_alternatives_webview(request: TranslationRequest, tab: String, count: u32, window: tauri::WebviewWindow) -> Result<(), String> {
+    let credential = credentials::resolve(window.app_handle(), request.credential.as_deref())?;
+    let settings = settings::current(window.app_handle());
+    let _ = window.emit("translation-metadata", &ResponseMetadata {
+        credential: credential.name.clone(),
+        model: settings.model.clone(),
+    });
+    let (request, source_language) = detect_target(&settings, request);
+    if let Some(source_language) = &source_language {
+        let _ = window.emit("source-language", source_language);
+    }
+    let _ = window.emit("target-language", &TabEvent { tab: &tab, data: &request.target_language });
+    let style = resolve_style(window.app_handle(), &settings, &request);
+    let glossary = glossary::load(window.app_handle());
+    let prompt = translation_prompt(&settings, &glossary, &request, &style, None);
+    println!("🔀 Requesting {} alternative translations to {}", count, request.target_language);
+
+    let chunk_window = window.clone();
+    let chunk_tab = tab.clone();
+    let mut alternatives = alternatives::generate(&credential.api_key, &settings.model, settings.temperature, &prompt, count, move |index, chunk| {
+        let _ = chunk_window.emit("translation-alternative-chunk", &TabEvent {
+            tab: &chunk_tab,
+            data: alternatives::AlternativeChunk { index, chunk },
+        });
+    })
+    .await?;
+
+    for alternative in &mut alternatives {
+        alternative.glossary_violations = check_glossary(&glossary, &request, &alternative.text);
+    }
+    let _ = window.emit("translation-alternatives", &TabEvent { tab: &tab, data: &alternatives });
+    Ok(())
+}
+
 #[tauri::command]
 async fn stream_translate(_request: TranslationRequest, _window: tauri::Window) -> Result<(), String> {
     // This is for the command handler - convert to WebviewWindow if needed
@@ -232,76 +747,487 @@ async fn stream_translate(_request: TranslationRequest, _window: tauri::Window)
     Err("Use stream_translate_webview instead".to_string())
 }
 
+/// Streams a chat completion, handing every content delta to `on_chunk`, and returns the full text.
+async fn stream_chat_completion<F: FnMut(&str)>(api_key: &str, payload: serde_json::Value, mut on_chunk: F) -> Result<String, String> {
+    use tokio_stream::StreamExt;
+
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post("https://api.openai.com/v1/chat/completions")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("HTTP error: {} - {}", status, error_text));
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut pending: Vec<u8> = Vec::new();
+    let mut buffer = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| e.to_string())?;
+        pending.extend_from_slice(&chunk);
+
+        // Events can be split across network chunks, so only consume complete lines
+        while let Some(newline) = pending.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim_end().strip_prefix("data: ") else {
+                continue;
+            };
+
+            if data == "[DONE]" {
+                return Ok(buffer);
+            }
+
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+                if let Some(content) = json["choices"][0]["delta"]["content"].as_str() {
+                    buffer.push_str(content);
+                    on_chunk(content);
+                }
+            }
+        }
+    }
+
+    Ok(buffer)
+}
+
 #[tauri::command]
-async fn start_audio_recording() -> Result<(), String> {
+async fn start_audio_recording(
+    app_handle: tauri::AppHandle,
+    include_microphone: Option<bool>,
+    silence_warning_seconds: Option<u64>,
+    failover: Option<bool>,
+) -> Result<(), String> {
     // Check if already recording
     if IS_RECORDING.load(Ordering::Relaxed) {
         return Err("Already recording".to_string());
     }
 
+    // Explicit arguments override the stored settings for this session
+    let settings = settings::current(&app_handle);
     let host = cpal::default_host();
     
     // Try to get system audio output device (loopback)
-    let device = match find_system_audio_device(&host) {
-        Some(dev) => dev,
+    let input = match find_system_capture_input(&host) {
+        Some(input) => input,
         None => {
             println!("⚠️ No system audio device found, falling back to default input");
-            host.default_input_device()
-                .ok_or("No audio device available")?
+            CaptureInput::Device(host.default_input_device()
+                .ok_or("No audio device available")?)
         }
     };
-    
-    let config = device.default_input_config()
-        .map_err(|e| format!("Failed to get default input config: {}", e))?;
-    
-    let sample_rate = config.sample_rate().0;
-    let channels = config.channels();
-    
-    println!("🎤 Starting audio recording - Sample rate: {}, Channels: {}", sample_rate, channels);
+
+    // The microphone gets its own track so diarization can tell "you" apart from the call
+    let microphone = if include_microphone.unwrap_or(settings.record_microphone) {
+        let system_name = input.name();
+        host.default_input_device()
+            .filter(|mic| mic.name().ok() != system_name)
+    } else {
+        None
+    };
     
     // Clear previous audio data and start recording
     if let Ok(mut audio_vec) = AUDIO_DATA.lock() {
         audio_vec.clear();
     }
+    if let Ok(mut mic_vec) = MIC_DATA.lock() {
+        mic_vec.clear();
+    }
+    if let Ok(mut pauses) = PAUSES.lock() {
+        pauses.clear();
+    }
+    if let Ok(mut session) = SESSION_INFO.lock() {
+        *session = SessionInfo {
+            started_at: Some(chrono::Local::now()),
+            device_name: input.name(),
+            microphone_device_name: microphone.as_ref().and_then(|mic| mic.name().ok()),
+        };
+    }
     
+    IS_PAUSED.store(false, Ordering::Relaxed);
     IS_RECORDING.store(true, Ordering::Relaxed);
+
+    let silence_warning_seconds = silence_warning_seconds.unwrap_or(settings.silence_warning_seconds) as f32;
+    let failover = failover.unwrap_or(settings.device_failover);
     
-    let audio_data_clone = AUDIO_DATA.clone();
-    let is_recording_clone = IS_RECORDING.clone();
+    let format = match spawn_capture_stream(app_handle.clone(), AudioSource::System, input, AUDIO_DATA.clone(), silence_warning_seconds, failover) {
+        Ok(format) => format,
+        Err(e) => {
+            IS_RECORDING.store(false, Ordering::Relaxed);
+            return Err(e);
+        }
+    };
+    println!("🎤 Starting audio recording - Sample rate: {}, Channels: {}", format.sample_rate, format.channels);
+    if let Ok(mut stored) = AUDIO_FORMAT.lock() {
+        *stored = Some(format);
+    }
+
+    let mic_format = match microphone {
+        Some(mic) => match spawn_capture_stream(app_handle, AudioSource::Microphone, CaptureInput::Device(mic), MIC_DATA.clone(), silence_warning_seconds, failover) {
+            Ok(mic_format) => {
+                println!("🎙️ Also recording microphone - Sample rate: {}, Channels: {}", mic_format.sample_rate, mic_format.channels);
+                Some(mic_format)
+            }
+            Err(e) => {
+                println!("⚠️ Microphone capture unavailable, recording system audio only: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    if let Ok(mut stored) = MIC_FORMAT.lock() {
+        *stored = mic_format;
+    }
+    if mic_format.is_none() {
+        if let Ok(mut session) = SESSION_INFO.lock() {
+            session.microphone_device_name = None;
+        }
+    }
     
-    let stream = match config.sample_format() {
-        cpal::SampleFormat::F32 => {
-            device.build_input_stream(
-                &config.into(),
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if is_recording_clone.load(Ordering::Relaxed) {
-                        if let Ok(mut audio_vec) = audio_data_clone.lock() {
-                            audio_vec.extend_from_slice(data);
+    Ok(())
+}
+
+/// Opens an input stream on its own thread and keeps it alive until `IS_RECORDING` is cleared.
+/// `cpal::Stream` is not `Send` on every platform, so it never leaves the capture thread.
+/// While recording, the thread also reports `audio-level` events, warns about sustained silence
+/// and, when `failover` is set, reopens the stream on the default device if the current one fails.
+fn spawn_capture_stream(
+    app_handle: tauri::AppHandle,
+    source: AudioSource,
+    input: CaptureInput,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    silence_warning_seconds: f32,
+    failover: bool,
+) -> Result<CaptureFormat, String> {
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let format = match input.format() {
+            Ok(format) => format,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        let meter = Arc::new(Mutex::new(LevelMeter::new(format.channels)));
+        let mut device_name = input.name();
+
+        let mut capture = match open_capture_stream(&input, buffer.clone(), meter.clone(), format) {
+            Ok(capture) => {
+                let _ = ready_tx.send(Ok(format));
+                Some(capture)
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+
+        let started = std::time::Instant::now();
+        let mut last_report = started;
+        let mut silence = SilenceDetector::new(silence_warning_seconds);
+        let report_interval = std::time::Duration::from_millis(LEVEL_REPORT_INTERVAL_MS);
+        let mut last_bytes = 0;
+        let mut last_progress = started;
+        let mut interrupted_at: Option<std::time::Instant> = None;
+        let mut last_recovery_attempt = started;
+
+        while IS_RECORDING.load(Ordering::Relaxed) {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+
+            // Stream errors (e.g. an unplugged headset) and stalled callbacks both end the current stream
+            let failure = match &capture {
+                Some(current) => current.failure.lock().ok().and_then(|mut failure| failure.take()).or_else(|| {
+                    let stalled = !IS_PAUSED.load(Ordering::Relaxed)
+                        && last_progress.elapsed() > std::time::Duration::from_secs(STREAM_STALL_SECONDS);
+                    stalled.then(|| format!("No audio received for {}s", STREAM_STALL_SECONDS))
+                }),
+                None => None,
+            };
+            if let Some(error) = failure {
+                println!("⚠️ {:?} capture interrupted: {}", source, error);
+                capture = None;
+                interrupted_at = Some(std::time::Instant::now());
+                let _ = app_handle.emit("recording-interrupted", &RecordingInterrupted {
+                    source,
+                    device_name: device_name.clone(),
+                    error,
+                    will_recover: failover,
+                });
+            }
+
+            if capture.is_none() && failover && last_recovery_attempt.elapsed() >= std::time::Duration::from_secs(RECOVERY_RETRY_SECONDS) {
+                last_recovery_attempt = std::time::Instant::now();
+                match recover_capture_stream(source, buffer.clone(), meter.clone(), format) {
+                    Ok((recovered, name)) => {
+                        let gap_seconds = interrupted_at.take().map(|at| at.elapsed().as_secs_f32()).unwrap_or(0.0);
+                        println!("✅ {:?} capture recovered on {:?} after {:.1}s", source, name, gap_seconds);
+                        if source == AudioSource::System {
+                            record_gap(gap_seconds);
                         }
+                        capture = Some(recovered);
+                        device_name = name;
+                        last_progress = std::time::Instant::now();
+                        let _ = app_handle.emit("recording-recovered", &RecordingRecovered {
+                            source,
+                            device_name: device_name.clone(),
+                            gap_seconds,
+                        });
                     }
+                    Err(e) => println!("⚠️ {:?} capture recovery failed, retrying: {}", source, e),
+                }
+            }
+
+            let interval = last_report.elapsed();
+            if interval < report_interval {
+                continue;
+            }
+            last_report = std::time::Instant::now();
+
+            // A paused session is silent on purpose
+            if IS_PAUSED.load(Ordering::Relaxed) {
+                silence = SilenceDetector::new(silence_warning_seconds);
+                last_progress = std::time::Instant::now();
+                continue;
+            }
+
+            let (channels, bytes_captured) = match meter.lock() {
+                Ok(mut meter) => (meter.take_levels(), meter.bytes_captured()),
+                Err(_) => (vec![Default::default(); format.channels as usize], 0),
+            };
+            if bytes_captured != last_bytes {
+                last_bytes = bytes_captured;
+                last_progress = std::time::Instant::now();
+            }
+            let level = AudioLevel {
+                source,
+                channels,
+                elapsed_seconds: started.elapsed().as_secs_f32(),
+                bytes_captured,
+            };
+            let _ = app_handle.emit("audio-level", &level);
+
+            if let Some(silent_seconds) = silence.update(level.peak(), interval.as_secs_f32()) {
+                println!("🔇 No audio from {:?} capture for {:.0}s - is the device muted?", source, silent_seconds);
+                let _ = app_handle.emit("audio-silence-warning", &SilenceWarning { source, silent_seconds });
+            }
+        }
+        drop(capture);
+    });
+
+    ready_rx.recv()
+        .map_err(|_| "Audio capture thread exited unexpectedly".to_string())?
+}
+
+/// Where a capture reads from: a cpal input device or, on Linux, a PulseAudio/PipeWire monitor source.
+enum CaptureInput {
+    Device(cpal::Device),
+    #[cfg(target_os = "linux")]
+    Monitor(pulse::MonitorSource),
+}
+
+impl CaptureInput {
+    fn name(&self) -> Option<String> {
+        match self {
+            CaptureInput::Device(device) => device.name().ok(),
+            #[cfg(target_os = "linux")]
+            CaptureInput::Monitor(source) => Some(source.name.clone()),
+        }
+    }
+
+    fn format(&self) -> Result<CaptureFormat, String> {
+        match self {
+            CaptureInput::Device(device) => {
+                let config = device.default_input_config()
+                    .map_err(|e| format!("Failed to get default input config: {}", e))?;
+                
+                Ok(CaptureFormat {
+                    sample_rate: config.sample_rate().0,
+                    channels: config.channels(),
+                })
+            }
+            #[cfg(target_os = "linux")]
+            CaptureInput::Monitor(source) => Ok(source.format),
+        }
+    }
+}
+
+/// Keeps the underlying stream alive; dropping it stops capture.
+enum CaptureHandle {
+    Device { _stream: cpal::Stream },
+    #[cfg(target_os = "linux")]
+    Monitor { _capture: pulse::MonitorCapture },
+}
+
+/// A running input stream plus the error reported by its error callback, if any.
+struct CaptureStream {
+    _handle: CaptureHandle,
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+/// Opens `input` so that it appends to `buffer` in `session_format`, converting if the input differs.
+fn open_capture_stream(
+    input: &CaptureInput,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    meter: Arc<Mutex<LevelMeter>>,
+    session_format: CaptureFormat,
+) -> Result<CaptureStream, String> {
+    let is_recording_clone = IS_RECORDING.clone();
+    let is_paused_clone = IS_PAUSED.clone();
+    let failure = Arc::new(Mutex::new(None));
+    let failure_clone = failure.clone();
+
+    let on_samples = move |data: &[f32]| {
+        if let Ok(mut audio_vec) = buffer.lock() {
+            audio_vec.extend_from_slice(data);
+        }
+        if let Ok(mut meter) = meter.lock() {
+            meter.process(data);
+        }
+    };
+    let on_error = move |err: String| {
+        eprintln!("Audio recording error: {}", err);
+        if let Ok(mut failure) = failure_clone.lock() {
+            *failure = Some(err);
+        }
+    };
+
+    let handle = match input {
+        CaptureInput::Device(device) => {
+            let config = device.default_input_config()
+                .map_err(|e| format!("Failed to get default input config: {}", e))?;
+            
+            let device_format = CaptureFormat {
+                sample_rate: config.sample_rate().0,
+                channels: config.channels(),
+            };
+            let mut converter = SampleConverter::new(device_format, session_format);
+            
+            let stream = match config.sample_format() {
+                cpal::SampleFormat::F32 => {
+                    device.build_input_stream(
+                        &config.into(),
+                        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                            if is_recording_clone.load(Ordering::Relaxed) && !is_paused_clone.load(Ordering::Relaxed) {
+                                on_samples(&converter.process(data));
+                            }
+                        },
+                        move |err| on_error(err.to_string()),
+                        None,
+                    )
                 },
-                |err| eprintln!("Audio recording error: {}", err),
-                None,
-            )
-        },
-        _ => return Err("Unsupported sample format".to_string()),
-    }.map_err(|e| format!("Failed to build input stream: {}", e))?;
-    
-    stream.play().map_err(|e| format!("Failed to start stream: {}", e))?;
-    
-    // Keep the stream alive by creating a handle that will be dropped later
-    std::mem::forget(stream);
+                _ => return Err("Unsupported sample format".to_string()),
+            }.map_err(|e| format!("Failed to build input stream: {}", e))?;
+            
+            stream.play().map_err(|e| format!("Failed to start stream: {}", e))?;
+            CaptureHandle::Device { _stream: stream }
+        }
+        #[cfg(target_os = "linux")]
+        CaptureInput::Monitor(source) => {
+            // parec converts to the session format itself
+            let capture = pulse::start_monitor_capture(
+                source,
+                session_format,
+                move |data| {
+                    if is_recording_clone.load(Ordering::Relaxed) && !is_paused_clone.load(Ordering::Relaxed) {
+                        on_samples(data);
+                    }
+                },
+                on_error,
+            )?;
+            CaptureHandle::Monitor { _capture: capture }
+        }
+    };
     
-    Ok(())
+    Ok(CaptureStream {
+        _handle: handle,
+        failure,
+    })
+}
+
+/// Best input for recording computer audio: a Pulse monitor source on Linux, otherwise a loopback device.
+fn find_system_capture_input(host: &cpal::Host) -> Option<CaptureInput> {
+    #[cfg(target_os = "linux")]
+    if let Some(source) = pulse::default_monitor_source() {
+        return Some(CaptureInput::Monitor(source));
+    }
+
+    find_system_audio_device(host).map(CaptureInput::Device)
+}
+
+/// Reopens a failed capture on whatever input is now the best match for its source.
+fn recover_capture_stream(
+    source: AudioSource,
+    buffer: Arc<Mutex<Vec<f32>>>,
+    meter: Arc<Mutex<LevelMeter>>,
+    session_format: CaptureFormat,
+) -> Result<(CaptureStream, Option<String>), String> {
+    let host = cpal::default_host();
+    let input = match source {
+        AudioSource::System => find_system_capture_input(&host)
+            .or_else(|| host.default_input_device().map(CaptureInput::Device)),
+        AudioSource::Microphone => host.default_input_device().map(CaptureInput::Device),
+    }
+    .ok_or("No audio device available")?;
+
+    let name = input.name();
+    let capture = open_capture_stream(&input, buffer, meter, session_format)?;
+    Ok((capture, name))
+}
+
+fn input_device_names(host: &cpal::Host) -> Vec<String> {
+    #[allow(unused_mut)]
+    let mut names: Vec<String> = host.input_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default();
+
+    // ALSA rarely lists monitor sources, so ask the sound server as well
+    #[cfg(target_os = "linux")]
+    if let Ok(sources) = pulse::list_monitor_sources() {
+        names.extend(sources.into_iter().map(|source| source.name));
+    }
+    names
+}
+
+/// Polls the input device list for the lifetime of the app and emits `audio-device-changed`.
+fn spawn_device_watcher(app_handle: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let host = cpal::default_host();
+        let mut known = input_device_names(&host);
+
+        loop {
+            std::thread::sleep(std::time::Duration::from_secs(DEVICE_POLL_SECONDS));
+            let current = input_device_names(&host);
+            if let Some(change) = capture::diff_devices(&known, &current) {
+                println!("🔌 Audio devices changed - added: {:?}, removed: {:?}", change.added, change.removed);
+                let _ = app_handle.emit("audio-device-changed", &change);
+                known = current;
+            }
+        }
+    });
 }
 
 #[tauri::command]
-async fn stop_audio_recording() -> Result<AudioRecordingResult, String> {
+async fn stop_audio_recording(app_handle: tauri::AppHandle) -> Result<AudioRecordingResult, String> {
     // Check if currently recording
     if !IS_RECORDING.load(Ordering::Relaxed) {
         return Err("No active recording session".to_string());
     }
     
+    // Stopping while paused closes the open pause
+    if IS_PAUSED.load(Ordering::Relaxed) {
+        end_pause()?;
+    }
+    
     // Stop recording
     IS_RECORDING.store(false, Ordering::Relaxed);
     
@@ -313,17 +1239,219 @@ async fn stop_audio_recording() -> Result<AudioRecordingResult, String> {
         .map_err(|_| "Failed to acquire audio data lock")?
         .clone();
     
+    let format = *AUDIO_FORMAT.lock()
+        .map_err(|_| "Failed to acquire audio format lock")?;
+    let microphone_format = *MIC_FORMAT.lock()
+        .map_err(|_| "Failed to acquire microphone format lock")?;
+    let microphone_data = match microphone_format {
+        Some(_) => Some(MIC_DATA.lock()
+            .map_err(|_| "Failed to acquire microphone data lock")?
+            .clone()),
+        None => None,
+    };
+    
+    let pauses = PAUSES.lock()
+        .map_err(|_| "Failed to acquire pause list lock")?
+        .clone();
+    
     println!("🎤 Audio recording stopped. Recorded {} samples", audio_data.len());
+    if let Some(mic) = &microphone_data {
+        println!("🎙️ Microphone track: {} samples", mic.len());
+    }
+    if !pauses.is_empty() {
+        println!("⏸️ Recording contains {} pause(s)", pauses.len());
+    }
+
+    // Persist the session before anything else can go wrong with it
+    let recording_id = match format {
+        Some(format) => {
+            let tracks = RecordedTracks {
+                audio_data: audio_data.clone(),
+                format,
+                microphone_data: microphone_data.clone(),
+                microphone_format,
+                pauses: pauses.clone(),
+            };
+            let session = SESSION_INFO.lock()
+                .map_err(|_| "Failed to acquire session info lock")?
+                .clone();
+            let save_handle = app_handle.clone();
+            match tokio::task::spawn_blocking(move || recordings::save_recording(&save_handle, &tracks, &session)).await {
+                Ok(Ok(entry)) => entry.map(|entry| entry.id),
+                Ok(Err(e)) => {
+                    println!("⚠️ Failed to save recording: {}", e);
+                    None
+                }
+                Err(e) => {
+                    println!("⚠️ Failed to save recording: {}", e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
     
     Ok(AudioRecordingResult {
         success: true,
         audio_data: Some(audio_data),
+        format,
+        microphone_data,
+        microphone_format,
+        pauses,
+        recording_id,
         error: None,
     })
 }
 
 #[tauri::command]
-async fn transcribe_audio(audio_data: Vec<f32>) -> Result<TranscriptionResult, String> {
+async fn pause_audio_recording(app_handle: tauri::AppHandle) -> Result<(), String> {
+    if !IS_RECORDING.load(Ordering::Relaxed) {
+        return Err("No active recording session".to_string());
+    }
+    if IS_PAUSED.swap(true, Ordering::Relaxed) {
+        return Err("Recording is already paused".to_string());
+    }
+
+    *PAUSE_STARTED.lock().map_err(|_| "Failed to acquire pause lock")? = Some(std::time::Instant::now());
+
+    println!("⏸️ Audio recording paused");
+    let _ = app_handle.emit("recording-paused", ());
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_audio_recording(app_handle: tauri::AppHandle) -> Result<(), String> {
+    if !IS_RECORDING.load(Ordering::Relaxed) {
+        return Err("No active recording session".to_string());
+    }
+    if !IS_PAUSED.load(Ordering::Relaxed) {
+        return Err("Recording is not paused".to_string());
+    }
+
+    let pause = end_pause()?;
+
+    println!("▶️ Audio recording resumed after {:.1}s pause", pause.duration_seconds);
+    let _ = app_handle.emit("recording-resumed", &pause);
+    Ok(())
+}
+
+/// Closes the current pause and records where it sits in the captured audio.
+fn end_pause() -> Result<RecordingPause, String> {
+    let started = PAUSE_STARTED.lock()
+        .map_err(|_| "Failed to acquire pause lock")?
+        .take();
+
+    let pause = RecordingPause {
+        at_seconds: captured_seconds()?,
+        duration_seconds: started.map(|instant| instant.elapsed().as_secs_f32()).unwrap_or(0.0),
+    };
+    PAUSES.lock()
+        .map_err(|_| "Failed to acquire pause list lock")?
+        .push(pause);
+    IS_PAUSED.store(false, Ordering::Relaxed);
+
+    Ok(pause)
+}
+
+/// Position of the end of the system track, which defines the transcript timeline.
+fn captured_seconds() -> Result<f32, String> {
+    let captured_samples = AUDIO_DATA.lock()
+        .map_err(|_| "Failed to acquire audio data lock")?
+        .len();
+    let seconds = match *AUDIO_FORMAT.lock().map_err(|_| "Failed to acquire audio format lock")? {
+        Some(format) => captured_samples as f32 / (format.sample_rate as f32 * format.channels.max(1) as f32),
+        None => 0.0,
+    };
+    Ok(seconds)
+}
+
+/// Marks an outage in the capture the same way a pause is marked, so transcripts show the gap.
+fn record_gap(duration_seconds: f32) {
+    let at_seconds = captured_seconds().unwrap_or(0.0);
+    if let Ok(mut pauses) = PAUSES.lock() {
+        pauses.push(RecordingPause { at_seconds, duration_seconds });
+    }
+}
+
+/// Returns the tracks of the most recently finished recording, which stay
+/// buffered until the next recording starts.
+fn last_recording() -> Result<RecordedTracks, String> {
+    if IS_RECORDING.load(Ordering::Relaxed) {
+        return Err("Recording still in progress".to_string());
+    }
+
+    let format = (*AUDIO_FORMAT.lock()
+        .map_err(|_| "Failed to acquire audio format lock")?)
+        .ok_or("No recording available")?;
+    let microphone_format = *MIC_FORMAT.lock()
+        .map_err(|_| "Failed to acquire microphone format lock")?;
+    let audio_data = AUDIO_DATA.lock()
+        .map_err(|_| "Failed to acquire audio data lock")?
+        .clone();
+    let microphone_data = match microphone_format {
+        Some(_) => Some(MIC_DATA.lock()
+            .map_err(|_| "Failed to acquire microphone data lock")?
+            .clone()),
+        None => None,
+    };
+
+    if audio_data.is_empty() && microphone_data.as_ref().is_none_or(|mic| mic.is_empty()) {
+        return Err("Last recording contains no audio".to_string());
+    }
+
+    let pauses = PAUSES.lock()
+        .map_err(|_| "Failed to acquire pause list lock")?
+        .clone();
+
+    Ok(RecordedTracks {
+        audio_data,
+        format,
+        microphone_data,
+        microphone_format,
+        pauses,
+    })
+}
+
+#[tauri::command]
+async fn transcribe_audio(
+    app_handle: tauri::AppHandle,
+    audio_data: Vec<f32>,
+    format: Option<CaptureFormat>,
+    pauses: Option<Vec<RecordingPause>>,
+    recording_id: Option<String>,
+) -> Result<TranscriptionResult, String> {
+    // The buffer comes from the last recording; its format is known here when the caller doesn't send it
+    let format = match format {
+        Some(format) => format,
+        None => (*AUDIO_FORMAT.lock()
+            .map_err(|_| "Failed to acquire audio format lock")?)
+            .ok_or("Capture format of the audio is unknown")?,
+    };
+    let pauses = match pauses {
+        Some(pauses) => pauses,
+        None => PAUSES.lock()
+            .map_err(|_| "Failed to acquire pause list lock")?
+            .clone(),
+    };
+    let processing = settings::current(&app_handle).audio_processing;
+    let result = transcribe_audio_data(&app_handle, audio_data, format, &pauses, &processing).await?;
+
+    if let Some(id) = recording_id {
+        if let Err(e) = recordings::record_transcription(&app_handle, &id, result.transcription.as_deref(), result.error.as_deref()) {
+            println!("⚠️ Failed to store transcription for recording {}: {}", id, e);
+        }
+    }
+
+    Ok(result)
+}
+
+async fn transcribe_audio_data(
+    app_handle: &tauri::AppHandle,
+    audio_data: Vec<f32>,
+    format: CaptureFormat,
+    pauses: &[RecordingPause],
+    processing: &dsp::AudioProcessingConfig,
+) -> Result<TranscriptionResult, String> {
     if audio_data.is_empty() {
         return Ok(TranscriptionResult {
             success: false,
@@ -332,13 +1460,40 @@ async fn transcribe_audio(audio_data: Vec<f32>) -> Result<TranscriptionResult, S
         });
     }
     
-    // Read API key from environment variable
-    let api_key = env::var("OPENAI_API_KEY")
-        .map_err(|_| "OPENAI_API_KEY environment variable not found")?;
+    let api_key = credentials::api_key(app_handle)?;
     
-    // Convert audio to WAV format
-    let wav_data = convert_audio_to_wav(&audio_data, 44100, 1)
+    // Downmix the interleaved capture and clean up the signal, then convert audio to WAV format
+    let sample_rate = format.sample_rate;
+    let audio_data = diarization::downmix(&audio_data, format.channels);
+    let audio_data = dsp::process(&audio_data, sample_rate, processing);
+    let wav_data = convert_audio_to_wav(&audio_data, sample_rate, 1)
         .map_err(|e| format!("Failed to convert audio: {}", e))?;
+
+    // Pause markers need segment timestamps to be placed
+    if !pauses.is_empty() {
+        println!("🚀 Sending audio to OpenAI Whisper API ({} pause(s) to mark)...", pauses.len());
+        return Ok(match request_whisper_segments(&api_key, wav_data).await {
+            Ok(segments) if segments.iter().any(|segment| !segment.text.trim().is_empty()) => {
+                let transcription = diarization::format_plain_transcript(&segments, pauses);
+                println!("🎉 TRANSCRIPTION SUCCESS: \"{}\"", transcription);
+                TranscriptionResult {
+                    success: true,
+                    transcription: Some(transcription),
+                    error: None,
+                }
+            }
+            Ok(_) => TranscriptionResult {
+                success: false,
+                transcription: None,
+                error: Some("Whisper returned empty transcription (no speech detected)".to_string()),
+            },
+            Err(e) => TranscriptionResult {
+                success: false,
+                transcription: None,
+                error: Some(e),
+            },
+        });
+    }
     
     // Create multipart form for OpenAI Whisper API
     let client = reqwest::Client::new();
@@ -350,10 +1505,10 @@ async fn transcribe_audio(audio_data: Vec<f32>) -> Result<TranscriptionResult, S
         .part("file", reqwest::multipart::Part::bytes(wav_data)
             .file_name("audio.wav")
             .mime_str("audio/wav").unwrap())
-        .text("model", "whisper-1");
+        .text("model", TRANSCRIPTION_MODEL);
     
     println!("🚀 Sending audio to OpenAI Whisper API...");
-    let duration_seconds = audio_data.len() as f32 / 44100.0;
+    let duration_seconds = audio_data.len() as f32 / sample_rate as f32;
     println!("⏱️ Audio duration: {:.1}s (estimated transcription time: 5-15s)", duration_seconds);
     
     let start_time = std::time::Instant::now();
@@ -409,6 +1564,146 @@ async fn transcribe_audio(audio_data: Vec<f32>) -> Result<TranscriptionResult, S
     }
 }
 
+#[tauri::command]
+async fn transcribe_with_speakers(app_handle: tauri::AppHandle, request: DiarizedTranscriptionRequest) -> Result<DiarizedTranscriptionResult, String> {
+    if request.audio_data.is_empty() && request.microphone_data.as_ref().is_none_or(|mic| mic.is_empty()) {
+        return Ok(DiarizedTranscriptionResult {
+            success: false,
+            segments: None,
+            transcription: None,
+            error: Some("No audio data provided".to_string()),
+        });
+    }
+
+    // Read API key from environment variable
+    let api_key = credentials::api_key(&app_handle)?;
+
+    let tracks = RecordedTracks {
+        audio_data: request.audio_data,
+        format: request.format,
+        microphone_data: request.microphone_data,
+        microphone_format: request.microphone_format,
+        pauses: request.pauses.unwrap_or_default(),
+    };
+
+    let processing = settings::current(&app_handle).audio_processing;
+    let result = match transcribe_tracks(&api_key, &tracks, request.max_speakers, &processing).await {
+        Ok(segments) => {
+            let transcription = diarization::format_speaker_transcript(&segments, &tracks.pauses);
+            println!("🎉 DIARIZED TRANSCRIPTION SUCCESS: {} segments", segments.len());
+            DiarizedTranscriptionResult {
+                success: true,
+                segments: Some(segments),
+                transcription: Some(transcription),
+                error: None,
+            }
+        }
+        Err(e) => DiarizedTranscriptionResult {
+            success: false,
+            segments: None,
+            transcription: None,
+            error: Some(e),
+        },
+    };
+
+    if let Some(id) = request.recording_id {
+        if let Err(e) = recordings::record_transcription(&app_handle, &id, result.transcription.as_deref(), result.error.as_deref()) {
+            println!("⚠️ Failed to store transcription for recording {}: {}", id, e);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Transcribes each track separately and returns the speaker-labeled segments in chronological order.
+async fn transcribe_tracks(
+    api_key: &str,
+    tracks: &RecordedTracks,
+    max_speakers: Option<usize>,
+    processing: &dsp::AudioProcessingConfig,
+) -> Result<Vec<SpeakerSegment>, String> {
+    let mut system_segments = Vec::new();
+    if !tracks.audio_data.is_empty() {
+        let (samples, sample_rate) = diarization::prepare_for_analysis(&tracks.audio_data, tracks.format.sample_rate, tracks.format.channels);
+        let samples = dsp::process(&samples, sample_rate, processing);
+        let wav_data = convert_audio_to_wav(&samples, sample_rate, 1)
+            .map_err(|e| format!("Failed to convert audio: {}", e))?;
+
+        println!("🗣️ Clustering speakers in system audio ({:.1}s)...", samples.len() as f32 / sample_rate as f32);
+        let turns = tokio::task::spawn_blocking(move || diarization::diarize(&samples, sample_rate, max_speakers))
+            .await
+            .map_err(|e| format!("Diarization failed: {}", e))?;
+        let speaker_count = turns.iter().map(|turn| turn.speaker + 1).max().unwrap_or(0);
+        println!("👥 Detected {} speaker(s) in system audio", speaker_count);
+
+        let segments = request_whisper_segments(api_key, wav_data).await?;
+        system_segments = diarization::label_system_segments(segments, &turns);
+    }
+
+    let mut microphone_segments = Vec::new();
+    if let (Some(mic_data), Some(mic_format)) = (&tracks.microphone_data, tracks.microphone_format) {
+        if !mic_data.is_empty() {
+            let (samples, sample_rate) = diarization::prepare_for_analysis(mic_data, mic_format.sample_rate, mic_format.channels);
+            let samples = dsp::process(&samples, sample_rate, processing);
+            let wav_data = convert_audio_to_wav(&samples, sample_rate, 1)
+                .map_err(|e| format!("Failed to convert microphone audio: {}", e))?;
+
+            println!("🎙️ Transcribing microphone track...");
+            let segments = request_whisper_segments(api_key, wav_data).await?;
+            microphone_segments = diarization::label_microphone_segments(segments);
+        }
+    }
+
+    let segments = diarization::merge_tracks(microphone_segments, system_segments);
+    if segments.is_empty() {
+        println!("⚠️ Whisper returned empty transcription");
+        return Err("Whisper returned empty transcription (no speech detected)".to_string());
+    }
+
+    Ok(segments)
+}
+
+/// Sends a WAV file to Whisper and returns its timestamped segments.
+async fn request_whisper_segments(api_key: &str, wav_data: Vec<u8>) -> Result<Vec<TranscriptSegment>, String> {
+    let client = reqwest::Client::new();
+
+    let form = reqwest::multipart::Form::new()
+        .part("file", reqwest::multipart::Part::bytes(wav_data)
+            .file_name("audio.wav")
+            .mime_str("audio/wav").unwrap())
+        .text("model", TRANSCRIPTION_MODEL)
+        .text("response_format", "verbose_json")
+        .text("timestamp_granularities[]", "segment");
+
+    let start_time = std::time::Instant::now();
+    let response = client
+        .post("https://api.openai.com/v1/audio/transcriptions")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        println!("❌ Whisper API error {}: {}", status, error_text);
+        return Err(format!("API error {}: {}", status, error_text));
+    }
+
+    println!("✅ Received segments from OpenAI Whisper API (took {:.1}s)", start_time.elapsed().as_secs_f32());
+    let result: serde_json::Value = response.json().await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    let segments = result["segments"].as_array()
+        .ok_or("No segments in Whisper response")?
+        .iter()
+        .filter_map(|segment| serde_json::from_value::<TranscriptSegment>(segment.clone()).ok())
+        .collect();
+
+    Ok(segments)
+}
+
 fn find_system_audio_device(host: &cpal::Host) -> Option<cpal::Device> {
     // Try to find a system audio device for capturing computer audio
     let devices = match host.input_devices() {
@@ -474,6 +1769,15 @@ async fn list_audio_devices() -> Result<Vec<String>, String> {
         }
     }
     
+    // List PulseAudio/PipeWire monitor sources
+    #[cfg(target_os = "linux")]
+    if let Ok(sources) = pulse::list_monitor_sources() {
+        devices.push("=== MONITOR SOURCES ===".to_string());
+        for source in sources {
+            devices.push(format!("Monitor: {}", source.name));
+        }
+    }
+    
     // List output devices
     devices.push("=== OUTPUT DEVICES ===".to_string());
     if let Ok(output_devices) = host.output_devices() {
@@ -511,48 +1815,43 @@ fn convert_audio_to_wav(audio_data: &[f32], sample_rate: u32, channels: u16) ->
 }
 
 #[tauri::command]
-async fn summarize_text(text: String, api_key: String) -> Result<String, String> {
+async fn summarize_text(app_handle: tauri::AppHandle, request: SummarizeRequest) -> Result<SummarizeResponse, String> {
+    let text = request.text;
     if text.trim().is_empty() {
         return Err("No text provided to summarize".to_string());
     }
 
-    // Read API key from environment if not provided
-    let api_key = if api_key == "dummy" {
-        env::var("OPENAI_API_KEY")
-            .map_err(|_| "OPENAI_API_KEY environment variable not found")?
-    } else {
-        api_key
-    };
+    let credential = credentials::resolve(&app_handle, request.credential.as_deref())?;
 
+    let settings = settings::current(&app_handle);
+    let options = request.options.unwrap_or_else(|| settings.summary.clone());
+    options.validate()?;
     let client = reqwest::Client::new();
     
-    // Create a concise but comprehensive summary prompt
-    let system_prompt = "You are an expert text summarizer. Create a clear, concise summary that captures the key points and main ideas. Keep it informative but brief. Focus on the most important information.";
-    
-    let user_prompt = format!("Please summarize the following text:\n\n{}", text);
+    let prompt = summary::build_prompt(&settings, &text, &options);
     
     let payload = serde_json::json!({
-        "model": "gpt-4o-mini",
+        "model": settings.model,
         "messages": [
             {
                 "role": "system",
-                "content": system_prompt
+                "content": prompt.system
             },
             {
                 "role": "user", 
-                "content": user_prompt
+                "content": prompt.user
             }
         ],
-        "temperature": 0.3,
-        "max_tokens": 500
+        "temperature": settings.temperature,
+        "max_tokens": options.max_tokens()
     });
 
-    println!("📝 Summarizing text ({} chars) with OpenAI...", text.len());
+    println!("📝 Summarizing text ({} chars, {:?}) with OpenAI using '{}'...", text.len(), options.mode, credential.name);
     let start_time = std::time::Instant::now();
 
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Authorization", format!("Bearer {}", credential.api_key))
         .header("Content-Type", "application/json")
         .json(&payload)
         .send()
@@ -570,7 +1869,14 @@ async fn summarize_text(text: String, api_key: String) -> Result<String, String>
             let summary = summary.trim().to_string();
             println!("🎉 SUMMARY SUCCESS: \"{}\"", summary);
             println!("📋 Summary will be copied to clipboard!");
-            Ok(summary)
+            Ok(SummarizeResponse {
+                summary,
+                mode: options.mode,
+                metadata: ResponseMetadata {
+                    credential: credential.name,
+                    model: settings.model,
+                },
+            })
         } else {
             Err("No summary content in response".to_string())
         }
@@ -583,7 +1889,10 @@ async fn summarize_text(text: String, api_key: String) -> Result<String, String>
 }
 
 #[tauri::command]
-async fn show_summarizer_window(app_handle: tauri::AppHandle, text: String) -> Result<(), String> {
+async fn show_summarizer_window(app_handle: tauri::AppHandle, text: String, options: Option<summary::SummaryOptions>) -> Result<(), String> {
+    let options = options.unwrap_or_else(|| settings::current(&app_handle).summary);
+    options.validate()?;
+
     let window = tauri::WebviewWindowBuilder::new(
         &app_handle,
         "summarizer",
@@ -618,45 +1927,45 @@ async fn show_summarizer_window(app_handle: tauri::AppHandle, text: String) -> R
     }
     
     // Start the summarization
-    let _ = summarize_for_window(text, window).await;
+    let _ = summarize_for_window(text, options, window).await;
     
     Ok(())
 }
 
-async fn summarize_for_window(text: String, window: tauri::WebviewWindow) -> Result<(), String> {
-    // Read API key from environment variable
-    let api_key = env::var("OPENAI_API_KEY")
-        .map_err(|_| "OPENAI_API_KEY environment variable not found. Please check your .env file.".to_string())?;
+async fn summarize_for_window(text: String, options: summary::SummaryOptions, window: tauri::WebviewWindow) -> Result<(), String> {
+    let credential = credentials::resolve(window.app_handle(), None)?;
 
+    let settings = settings::current(window.app_handle());
+    let _ = window.emit("summary-metadata", &ResponseMetadata {
+        credential: credential.name.clone(),
+        model: settings.model.clone(),
+    });
     let client = reqwest::Client::new();
     
-    // Create a concise but comprehensive summary prompt
-    let system_prompt = "You are an expert text summarizer. Create a clear, concise summary that captures the key points and main ideas. Keep it informative but brief. Focus on the most important information.";
-    
-    let user_prompt = format!("Please summarize the following text:\n\n{}", text);
+    let prompt = summary::build_prompt(&settings, &text, &options);
     
     let payload = serde_json::json!({
-        "model": "gpt-4o-mini",
+        "model": settings.model,
         "messages": [
             {
                 "role": "system",
-                "content": system_prompt
+                "content": prompt.system
             },
             {
                 "role": "user", 
-                "content": user_prompt
+                "content": prompt.user
             }
         ],
-        "temperature": 0.3,
-        "max_tokens": 500
+        "temperature": settings.temperature,
+        "max_tokens": options.max_tokens()
     });
 
-    println!("📝 Summarizing text ({} chars) with OpenAI for window...", text.len());
+    println!("📝 Summarizing text ({} chars, {:?}) with OpenAI for window...", text.len(), options.mode);
     let start_time = std::time::Instant::now();
 
     let response = client
         .post("https://api.openai.com/v1/chat/completions")
-        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Authorization", format!("Bearer {}", credential.api_key))
         .header("Content-Type", "application/json")
         .json(&payload)
         .send()
@@ -691,19 +2000,32 @@ async fn summarize_for_window(text: String, window: tauri::WebviewWindow) -> Res
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Load environment variables from .env file
-    dotenvy::dotenv().ok();
-    
-    // Log whether API key is loaded
-    match env::var("OPENAI_API_KEY") {
-        Ok(_) => println!("✅ OpenAI API key loaded from environment"),
-        Err(_) => println!("❌ OpenAI API key not found. Please check your .env file."),
-    }
-    
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
+        .setup(|app| {
+            let store = settings::SettingsStore::load(app.handle())?;
+            app.manage(store);
+
+            // Translation works without the memory, so a broken database only costs the cache
+            match translation_memory::TranslationMemory::open(app.handle()) {
+                Ok(memory) => {
+                    app.manage(memory);
+                }
+                Err(e) => println!("⚠️ {}", e),
+            }
+
+            // Older setups keep the key in .env; move it into the keyring once
+            credentials::import_env_key(app.handle());
+            match credentials::api_key(app.handle()) {
+                Ok(_) => println!("✅ OpenAI API key loaded"),
+                Err(_) => println!("❌ OpenAI API key not found. Add one in the app or set OPENAI_API_KEY."),
+            }
+
+            spawn_device_watcher(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             translate_text, 
             get_clipboard_text, 
@@ -711,10 +2033,39 @@ pub fn run() {
             stream_translate,
             start_audio_recording,
             stop_audio_recording,
+            pause_audio_recording,
+            resume_audio_recording,
             transcribe_audio,
+            transcribe_with_speakers,
+            meeting_notes::generate_meeting_notes,
+            recordings::list_recordings,
+            recordings::retranscribe_recording,
+            recordings::export_recording,
+            recordings::delete_recording,
+            settings::get_settings,
+            settings::update_settings,
+            prompts::render_prompt,
+            glossary::get_glossary,
+            glossary::save_glossary,
+            glossary::import_glossary,
+            glossary::export_glossary,
+            translation_memory::list_translation_memory,
+            translation_memory::purge_translation_memory,
+            credentials::list_credentials,
+            credentials::set_api_key,
+            credentials::clear_api_key,
+            credentials::test_api_key,
             list_audio_devices,
             summarize_text,
-            show_summarizer_window
+            show_summarizer_window,
+            rewrite::rewrite_text,
+            rewrite::show_rewrite_window,
+            explain::explain_code,
+            explain::show_code_window,
+            custom_agents::list_custom_agents,
+            custom_agents::run_custom_agent,
+            pipeline::run_pipeline,
+            pipeline::show_pipeline_window
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
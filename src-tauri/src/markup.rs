@@ -0,0 +1,649 @@
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// Largest batch of segments sent in one request, in characters.
+const BATCH_CHARS: usize = 4000;
+const BATCH_SEGMENTS: usize = 40;
+
+/// Tags whose content is never translated.
+const HTML_RAW_TAGS: &[&str] = &["script", "style", "pre", "textarea", "code", "kbd", "samp", "var"];
+/// Tags that sit inside a sentence; everything else ends a segment.
+const HTML_INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "br", "cite", "code", "data", "dfn", "em", "font", "i", "img", "kbd", "mark",
+    "q", "s", "samp", "small", "span", "strong", "sub", "sup", "time", "u", "var", "wbr",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    Plain,
+    Markdown,
+    Html,
+    /// Source code: only `//`, `/* */` and `# ` comments are translated
+    Code,
+}
+
+/// Guesses the format from markup signals. Code is never guessed, it has to be asked for.
+pub fn detect_format(text: &str) -> DocumentFormat {
+    let has_html_tag = text.match_indices('<').any(|(i, _)| {
+        let rest = &text[i + 1..];
+        rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') && rest.contains('>')
+    });
+    if has_html_tag && text.contains("</") {
+        return DocumentFormat::Html;
+    }
+
+    // Headings, fences and tables settle it; lists, quotes and inline formatting also occur in
+    // plain text (a dash list, a quoted reply), so it takes two different kinds of them
+    let lines: Vec<&str> = text.lines().map(str::trim_start).collect();
+    let structural = lines.iter().any(|line| {
+        line.starts_with("# ") || line.starts_with("## ") || line.starts_with("### ") || line.starts_with("```")
+    }) || lines.iter().any(|line| is_table_separator(line));
+    if structural {
+        return DocumentFormat::Markdown;
+    }
+
+    let signals = [
+        lines.iter().any(|line| line.starts_with("- ") || line.starts_with("* ")),
+        lines.iter().any(|line| line.starts_with("> ")),
+        text.contains("**"),
+        text.contains("]("),
+        text.matches('`').count() >= 2,
+    ];
+    if signals.iter().filter(|&&signal| signal).count() >= 2 {
+        return DocumentFormat::Markdown;
+    }
+    DocumentFormat::Plain
+}
+
+/// The `|---|:---:|` line under a Markdown table header.
+fn is_table_separator(line: &str) -> bool {
+    let line = line.trim_end();
+    line.starts_with('|') && line.contains('-') && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+/// A run of translatable text. Formatting and code inside it are replaced by `⟦n⟧` tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub text: String,
+    pub placeholders: Vec<String>,
+}
+
+impl Segment {
+    /// Puts the protected markup back into a translated segment; `None` if the model lost,
+    /// duplicated or invented a token.
+    pub fn restore(&self, translated: &str) -> Option<String> {
+        let mut output = String::with_capacity(translated.len());
+        let mut seen = vec![false; self.placeholders.len()];
+        let mut rest = translated.trim();
+        while let Some(start) = rest.find('⟦') {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + '⟦'.len_utf8()..];
+            let end = after.find('⟧')?;
+            let index: usize = after[..end].trim().parse().ok()?;
+            let placeholder = self.placeholders.get(index.checked_sub(1)?)?;
+            if std::mem::replace(&mut seen[index - 1], true) {
+                return None;
+            }
+            output.push_str(placeholder);
+            rest = &after[end + '⟧'.len_utf8()..];
+        }
+        output.push_str(rest);
+        seen.iter().all(|&seen| seen).then_some(output)
+    }
+
+    /// The segment as it was in the source.
+    pub fn original(&self) -> String {
+        self.restore(&self.text).unwrap_or_else(|| self.text.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Verbatim(String),
+    Segment(usize),
+}
+
+/// A document split into untouched markup and translatable segments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pieces: Vec<Piece>,
+    pub segments: Vec<Segment>,
+}
+
+impl Document {
+    pub fn parse(text: &str, format: DocumentFormat) -> Document {
+        match format {
+            DocumentFormat::Plain => {
+                let mut builder = Builder::default();
+                builder.text(text);
+                builder.finish()
+            }
+            DocumentFormat::Markdown => parse_markdown(text),
+            DocumentFormat::Html => parse_html(text),
+            DocumentFormat::Code => parse_code(text),
+        }
+    }
+
+    /// Reassembles the document from one (already restored) text per segment.
+    pub fn assemble(&self, segments: &[String]) -> String {
+        self.pieces.iter()
+            .map(|piece| match piece {
+                Piece::Verbatim(text) => text.as_str(),
+                Piece::Segment(index) => segments[*index].as_str(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+enum Part {
+    Text(String),
+    Markup(String),
+}
+
+/// Collects the parts of the current block and turns each block into verbatim edges plus one segment.
+#[derive(Default)]
+struct Builder {
+    pieces: Vec<Piece>,
+    segments: Vec<Segment>,
+    block: Vec<Part>,
+}
+
+impl Builder {
+    fn verbatim(&mut self, text: &str) {
+        self.flush();
+        self.push_verbatim(text);
+    }
+
+    fn push_verbatim(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.pieces.last_mut() {
+            Some(Piece::Verbatim(existing)) => existing.push_str(text),
+            _ => self.pieces.push(Piece::Verbatim(text.to_string())),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        // URLs in running text are protected like code
+        let mut rest = text;
+        while let Some(start) = ["https://", "http://"].iter().filter_map(|scheme| rest.find(scheme)).min() {
+            let end = rest[start..].find(char::is_whitespace).map_or(rest.len(), |end| start + end);
+            // Trailing punctuation belongs to the sentence, not the URL
+            let end = start + rest[start..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']).len();
+            self.block.push(Part::Text(rest[..start].to_string()));
+            self.block.push(Part::Markup(rest[start..end].to_string()));
+            rest = &rest[end..];
+        }
+        self.block.push(Part::Text(rest.to_string()));
+    }
+
+    fn markup(&mut self, markup: &str) {
+        if self.block.is_empty() {
+            self.push_verbatim(markup);
+        } else if markup.trim().is_empty() {
+            self.block.push(Part::Text(markup.to_string()));
+        } else {
+            self.block.push(Part::Markup(markup.to_string()));
+        }
+    }
+
+    fn flush(&mut self) {
+        let parts = std::mem::take(&mut self.block);
+        if !parts.iter().any(|part| matches!(part, Part::Text(text) if text.chars().any(char::is_alphabetic))) {
+            for part in parts {
+                let (Part::Text(text) | Part::Markup(text)) = part;
+                self.push_verbatim(&text);
+            }
+            return;
+        }
+
+        // Markup and whitespace at the edges stay outside the segment
+        let first = parts.iter().position(|part| matches!(part, Part::Text(text) if !text.trim().is_empty())).unwrap_or(0);
+        let last = parts.iter().rposition(|part| matches!(part, Part::Text(text) if !text.trim().is_empty())).unwrap_or(0);
+        let mut leading = String::new();
+        let mut trailing = String::new();
+        let mut segment = Segment { text: String::new(), placeholders: Vec::new() };
+
+        for (index, part) in parts.into_iter().enumerate() {
+            match part {
+                Part::Text(text) | Part::Markup(text) if index < first => leading.push_str(&text),
+                Part::Text(text) | Part::Markup(text) if index > last => trailing.push_str(&text),
+                Part::Text(text) => {
+                    let mut text = text.as_str();
+                    if index == first {
+                        let trimmed = text.trim_start();
+                        leading.push_str(&text[..text.len() - trimmed.len()]);
+                        text = trimmed;
+                    }
+                    if index == last {
+                        let trimmed = text.trim_end();
+                        trailing.insert_str(0, &text[trimmed.len()..]);
+                        text = trimmed;
+                    }
+                    segment.text.push_str(text);
+                }
+                Part::Markup(markup) => {
+                    segment.placeholders.push(markup);
+                    segment.text.push_str(&format!("⟦{}⟧", segment.placeholders.len()));
+                }
+            }
+        }
+
+        self.push_verbatim(&leading);
+        self.pieces.push(Piece::Segment(self.segments.len()));
+        self.segments.push(segment);
+        self.push_verbatim(&trailing);
+    }
+
+    fn finish(mut self) -> Document {
+        self.flush();
+        Document {
+            pieces: self.pieces,
+            segments: self.segments,
+        }
+    }
+}
+
+fn parse_markdown(source: &str) -> Document {
+    let mut builder = Builder::default();
+    let mut cursor = 0;
+    let mut in_code_block = false;
+
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_FOOTNOTES | Options::ENABLE_TASKLISTS;
+    for (event, range) in Parser::new_ext(source, options).into_offset_iter() {
+        match event {
+            Event::Text(_) if !in_code_block && range.start >= cursor => {
+                // Whatever lies between two text nodes of a block (emphasis markers, inline code,
+                // link targets) becomes a token; before the first one it stays verbatim
+                builder.markup(&source[cursor..range.start]);
+                builder.text(&source[range.clone()]);
+                cursor = range.end;
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                builder.flush();
+                in_code_block = true;
+            }
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Start(tag) if !matches!(tag, Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }) => builder.flush(),
+            Event::End(tag) if !matches!(tag, TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link | TagEnd::Image) => builder.flush(),
+            _ => {}
+        }
+    }
+
+    builder.verbatim(&source[cursor..]);
+    builder.finish()
+}
+
+/// Lowercase tag name of `<tag ...>` or `</tag>`.
+fn html_tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn parse_html(source: &str) -> Document {
+    let mut builder = Builder::default();
+    let mut rest = source;
+
+    while !rest.is_empty() {
+        let is_tag = rest.starts_with("<!--")
+            || (rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!'));
+        if !is_tag {
+            let step = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[step..].find('<').map_or(rest.len(), |end| end + step);
+            builder.text(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            builder.markup(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = &rest[..end];
+        let name = html_tag_name(tag);
+        let inline = HTML_INLINE_TAGS.contains(&name.as_str());
+
+        // Raw elements are protected together with their content
+        let element_end = if HTML_RAW_TAGS.contains(&name.as_str()) && !tag.starts_with("</") {
+            let closing = format!("</{}", name);
+            rest.to_ascii_lowercase()[end..]
+                .find(&closing)
+                .map(|close| end + close)
+                .and_then(|close| rest[close..].find('>').map(|gt| close + gt + 1))
+                .unwrap_or(end)
+        } else {
+            end
+        };
+
+        if inline {
+            builder.markup(&rest[..element_end]);
+        } else {
+            builder.verbatim(&rest[..element_end]);
+        }
+        rest = &rest[element_end..];
+    }
+    builder.finish()
+}
+
+fn parse_code(source: &str) -> Document {
+    let mut builder = Builder::default();
+    let mut rest = source;
+    // Consecutive line comments form one segment so sentences can span lines
+    let mut in_line_comment = false;
+
+    while !rest.is_empty() {
+        let consumed = source.len() - rest.len();
+        let line_start = consumed == 0 || source[..consumed].ends_with('\n');
+        let indent = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let trimmed = &rest[indent..];
+
+        let marker = if trimmed.starts_with("//") {
+            Some(trimmed.len() - trimmed.trim_start_matches('/').len())
+        } else if line_start && (trimmed.starts_with("# ") || trimmed.starts_with("#\n")) {
+            Some(1)
+        } else {
+            None
+        };
+
+        if let (true, Some(marker)) = (line_start, marker) {
+            let line_end = rest.find('\n').map_or(rest.len(), |end| end + 1);
+            let prefix = &rest[..indent + marker];
+            if in_line_comment {
+                builder.markup(prefix);
+            } else {
+                builder.verbatim(prefix);
+            }
+            let comment = &rest[indent + marker..line_end];
+            let body = comment.trim_end_matches('\n');
+            builder.text(body);
+            builder.markup(&comment[body.len()..]);
+            in_line_comment = true;
+            rest = &rest[line_end..];
+            continue;
+        }
+        if in_line_comment {
+            builder.flush();
+            in_line_comment = false;
+        }
+
+        // Skip code up to the next comment, stepping over string literals
+        let mut end = 0;
+        let mut chars = rest.char_indices();
+        let mut quote: Option<char> = None;
+        while let Some((i, c)) = chars.next() {
+            match quote {
+                Some(_) if c == '\\' => {
+                    chars.next();
+                }
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '"' || c == '\'' || c == '`' => quote = Some(c),
+                None if rest[i..].starts_with("//") || rest[i..].starts_with("/*") => {
+                    end = i;
+                    break;
+                }
+                None if c == '\n' => {
+                    end = i + 1;
+                    break;
+                }
+                None => {}
+            }
+            end = i + c.len_utf8();
+        }
+
+        if end == 0 && rest.starts_with("//") {
+            // A trailing comment after code on the same line
+            let line_end = rest.find('\n').unwrap_or(rest.len());
+            let body_start = 2 + rest[2..].len() - rest[2..].trim_start_matches('/').len();
+            builder.verbatim(&rest[..body_start]);
+            builder.text(&rest[body_start..line_end]);
+            builder.flush();
+            rest = &rest[line_end..];
+        } else if end == 0 && rest.starts_with("/*") {
+            let close = rest[2..].find("*/").map_or(rest.len(), |close| close + 4);
+            let opener = 2 + rest[2..].len() - rest[2..].trim_start_matches(['*', '!']).len();
+            let closer = if rest[..close].ends_with("*/") { close - 2 } else { close };
+            builder.verbatim(&rest[..opener.min(closer)]);
+            builder.text(&rest[opener.min(closer)..closer]);
+            builder.verbatim(&rest[closer..close]);
+            rest = &rest[close..];
+        } else {
+            builder.verbatim(&rest[..end.max(1)]);
+            rest = &rest[end.max(1)..];
+        }
+    }
+    builder.finish()
+}
+
+/// Result of translating a structured document.
+pub struct DocumentTranslation {
+    pub text: String,
+    /// Segments the model could not translate with their markup intact; they are kept in the original
+    pub untranslated_segments: usize,
+}
+
+fn format_instructions(format: DocumentFormat) -> String {
+    let kind = match format {
+        DocumentFormat::Plain => "plain text",
+        DocumentFormat::Markdown => "Markdown",
+        DocumentFormat::Html => "HTML",
+        DocumentFormat::Code => "source code comment",
+    };
+    format!(
+        "The input is a JSON object {{\"segments\": [...]}} holding the text fragments of one {} document, in order. \
+         Translate every segment and respond with a JSON object {{\"segments\": [...]}} containing exactly as many translated strings in the same order. \
+         Tokens such as ⟦1⟧ stand for formatting, links and code: keep each token exactly once, move it to where it belongs in the translated sentence, and never translate or change it.",
+        kind
+    )
+}
+
+/// Translates `segments[indices]` in batches; returns a translation per index, `None` where the model's answer was unusable.
+async fn translate_segments(api_key: &str, settings: &Settings, system_prompt: &str, segments: &[Segment], indices: &[usize]) -> Result<Vec<Option<String>>, String> {
+    let mut results = Vec::with_capacity(indices.len());
+    let mut batch_start = 0;
+
+    while batch_start < indices.len() {
+        let mut batch_end = batch_start;
+        let mut chars = 0;
+        while batch_end < indices.len() && batch_end - batch_start < BATCH_SEGMENTS && (batch_end == batch_start || chars + segments[indices[batch_end]].text.len() <= BATCH_CHARS) {
+            chars += segments[indices[batch_end]].text.len();
+            batch_end += 1;
+        }
+        let batch: Vec<&str> = indices[batch_start..batch_end].iter().map(|&i| segments[i].text.as_str()).collect();
+
+        let payload = serde_json::json!({
+            "model": settings.model,
+            "messages": [
+                { "role": "system", "content": system_prompt },
+                { "role": "user", "content": serde_json::json!({ "segments": batch }).to_string() }
+            ],
+            "temperature": settings.temperature,
+            "response_format": { "type": "json_object" },
+            "stream": true
+        });
+        println!("🧩 Translating {} segment(s) ({} chars)...", batch.len(), chars);
+        let response = crate::stream_chat_completion(api_key, payload, |_| {}).await?;
+
+        let translated: Vec<Option<String>> = serde_json::from_str::<serde_json::Value>(&response)
+            .ok()
+            .and_then(|value| value["segments"].as_array().cloned())
+            .filter(|translated| translated.len() == batch.len())
+            .map(|translated| translated.iter().map(|value| value.as_str().map(str::to_string)).collect())
+            .unwrap_or_else(|| vec![None; batch.len()]);
+        results.extend(translated);
+        batch_start = batch_end;
+    }
+    Ok(results)
+}
+
+/// Translates only the text of `document`, leaving markup, code and URLs untouched.
+/// `system_prompt` is the rendered translation prompt (language, glossary, tone).
+pub async fn translate_document(api_key: &str, settings: &Settings, system_prompt: &str, document: &Document, format: DocumentFormat) -> Result<DocumentTranslation, String> {
+    let system_prompt = format!("{}\n\n{}", system_prompt, format_instructions(format));
+    let mut translated: Vec<Option<String>> = vec![None; document.segments.len()];
+
+    let all: Vec<usize> = (0..document.segments.len()).collect();
+    for (index, result) in all.iter().zip(translate_segments(api_key, settings, &system_prompt, &document.segments, &all).await?) {
+        translated[*index] = result.and_then(|text| document.segments[*index].restore(&text));
+    }
+
+    // One more try for segments whose tokens were lost, each on its own
+    let failed: Vec<usize> = all.iter().copied().filter(|&i| translated[i].is_none()).collect();
+    for &index in &failed {
+        if let Some(Some(text)) = translate_segments(api_key, settings, &system_prompt, &document.segments, &[index]).await?.into_iter().next() {
+            translated[index] = document.segments[index].restore(&text);
+        }
+    }
+
+    let untranslated_segments = translated.iter().filter(|text| text.is_none()).count();
+    if untranslated_segments > 0 {
+        println!("⚠️ {} segment(s) kept untranslated because their markup could not be preserved", untranslated_segments);
+    }
+    let segments: Vec<String> = translated.into_iter()
+        .zip(&document.segments)
+        .map(|(text, segment)| text.unwrap_or_else(|| segment.original()))
+        .collect();
+
+    Ok(DocumentTranslation {
+        text: document.assemble(&segments),
+        untranslated_segments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = "# Release notes\n\nShorty now supports **bold** and `inline_code()` in [the docs](https://example.com/docs).\n\n| Feature | Status |\n|---------|--------|\n| Glossary | Done |\n\n```rust\nlet x = 1; // not translated\n```\n\n- First item\n- Second item with https://example.com/path.\n";
+    const HTML: &str = "<h1>Welcome</h1>\n<p>Click <a href=\"/start\">here</a> to <b>start</b>.<br>Then run <code>shorty --help</code>.</p>\n<script>var s = \"text\";</script>\n<!-- note -->\n";
+    const CODE: &str = "// Opens the window\n// and waits for input.\nfn open() {\n    let url = \"http://a//b\"; // trailing note\n    /* block comment */\n}\n";
+
+    /// Stand-in for the model: uppercases everything outside the tokens.
+    fn shout(segment: &Segment) -> String {
+        let mut output = String::new();
+        let mut in_token = false;
+        for c in segment.text.chars() {
+            match c {
+                '⟦' => in_token = true,
+                '⟧' => in_token = false,
+                _ => {}
+            }
+            if in_token || c == '⟧' {
+                output.push(c);
+            } else {
+                output.extend(c.to_uppercase());
+            }
+        }
+        output
+    }
+
+    fn round_trip(source: &str, format: DocumentFormat) -> Document {
+        let document = Document::parse(source, format);
+        let originals: Vec<String> = document.segments.iter().map(Segment::original).collect();
+        assert_eq!(document.assemble(&originals), source);
+        document
+    }
+
+    fn translate_with(document: &Document, translate: fn(&Segment) -> String) -> String {
+        let segments: Vec<String> = document.segments.iter().map(|segment| segment.restore(&translate(segment)).unwrap()).collect();
+        document.assemble(&segments)
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        let document = round_trip(MARKDOWN, DocumentFormat::Markdown);
+        let translated = translate_with(&document, shout);
+
+        assert!(translated.contains("# RELEASE NOTES"));
+        assert!(translated.contains("**BOLD**"));
+        assert!(translated.contains("`inline_code()`"));
+        assert!(translated.contains("[THE DOCS](https://example.com/docs)"));
+        assert!(translated.contains("| GLOSSARY | DONE |"));
+        assert!(translated.contains("let x = 1; // not translated"));
+        assert!(translated.contains("https://example.com/path."));
+    }
+
+    #[test]
+    fn html_round_trips() {
+        let document = round_trip(HTML, DocumentFormat::Html);
+        let translated = translate_with(&document, shout);
+
+        assert!(translated.contains("<h1>WELCOME</h1>"));
+        assert!(translated.contains("CLICK <a href=\"/start\">HERE</a> TO <b>START</b>.<br>THEN RUN <code>shorty --help</code>."));
+        assert!(translated.contains("<script>var s = \"text\";</script>"));
+        assert!(translated.contains("<!-- note -->"));
+    }
+
+    #[test]
+    fn html_with_non_ascii_text_round_trips() {
+        let document = round_trip("<p>Übersicht</p>\n<p>日本語 <b>テキスト</b></p>", DocumentFormat::Html);
+        let translated = translate_with(&document, shout);
+
+        assert!(translated.contains("<p>ÜBERSICHT</p>"));
+        assert!(translated.contains("<p>日本語 <b>テキスト</b></p>"));
+    }
+
+    #[test]
+    fn code_comments_round_trip() {
+        let document = round_trip(CODE, DocumentFormat::Code);
+        let translated = translate_with(&document, shout);
+
+        assert!(translated.contains("// OPENS THE WINDOW\n// AND WAITS FOR INPUT.\nfn open() {"));
+        assert!(translated.contains("let url = \"http://a//b\"; // TRAILING NOTE"));
+        assert!(translated.contains("/* BLOCK COMMENT */"));
+    }
+
+    #[test]
+    fn restore_rejects_lost_or_duplicated_tokens() {
+        let segment = Segment {
+            text: "Run ⟦1⟧ now".to_string(),
+            placeholders: vec!["`cmd`".to_string()],
+        };
+        assert_eq!(segment.restore("Führe ⟦1⟧ jetzt aus").as_deref(), Some("Führe `cmd` jetzt aus"));
+        assert_eq!(segment.restore("Führe jetzt aus"), None);
+        assert_eq!(segment.restore("⟦1⟧ ⟦1⟧"), None);
+        assert_eq!(segment.restore("⟦2⟧"), None);
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(detect_format(MARKDOWN), DocumentFormat::Markdown);
+        assert_eq!(detect_format(HTML), DocumentFormat::Html);
+        assert_eq!(detect_format("Just a sentence. With 3 < 4."), DocumentFormat::Plain);
+    }
+
+    #[test]
+    fn detects_markdown_from_structure_or_two_signals() {
+        assert_eq!(detect_format("## Agenda\nBudget and hiring."), DocumentFormat::Markdown);
+        assert_eq!(detect_format("Run this:\n```\nshorty --help\n```"), DocumentFormat::Markdown);
+        assert_eq!(detect_format("| Name | Role |\n|------|:----:|\n| Ana | Lead |"), DocumentFormat::Markdown);
+        assert_eq!(detect_format("Steps:\n- Open **Settings**\n- Pick a language"), DocumentFormat::Markdown);
+        assert_eq!(detect_format("See [the guide](https://example.com) and run `shorty`."), DocumentFormat::Markdown);
+    }
+
+    #[test]
+    fn plain_text_with_a_single_markdown_like_signal_stays_plain() {
+        let dash_list = "Hi team,\n\nthings to bring tomorrow:\n- laptop\n- charger\n- notes from Monday\n\nThanks!";
+        assert_eq!(detect_format(dash_list), DocumentFormat::Plain);
+
+        let quoted_reply = "Sounds good to me.\n\n> Can we move the call to 3pm?\n> Thanks, Ana";
+        assert_eq!(detect_format(quoted_reply), DocumentFormat::Plain);
+
+        let star_list = "Shopping:\n* milk\n* bread";
+        assert_eq!(detect_format(star_list), DocumentFormat::Plain);
+        assert_eq!(detect_format("Totals | 12 | 14 |\n| a | b |"), DocumentFormat::Plain);
+    }
+}
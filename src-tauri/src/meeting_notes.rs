@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+
+use crate::{credentials, diarization, settings};
+
+const MEETING_NOTES_PROMPT: &str = "You are an expert meeting assistant. You receive a speaker-labeled meeting transcript and write concise meeting notes in Markdown. Use exactly these sections, in this order:
+## Summary
+A short paragraph covering the purpose and outcome of the meeting.
+## Decisions
+A bullet list of decisions that were made.
+## Action Items
+A task list in the form `- [ ] Task — **Owner:** Name`. Use the speaker labels from the transcript as owners and write `Unassigned` when no owner is clear.
+## Open Questions
+A bullet list of questions that were raised but not resolved.
+Write `None` under a section when there is nothing to report. Lines marked as paused are off-the-record gaps; do not speculate about them. Only use information from the transcript and do not add a title.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingNotesResult {
+    pub markdown: String,
+    pub path: String,
+}
+
+/// Assembles the final note: title, generated sections and the full transcript for reference.
+pub fn render_meeting_note(created: &chrono::DateTime<chrono::Local>, notes: &str, transcript: &str) -> String {
+    format!(
+        "# Meeting Notes — {}\n\n{}\n\n## Transcript\n\n{}\n",
+        created.format("%Y-%m-%d %H:%M"),
+        notes.trim(),
+        transcript
+    )
+}
+
+pub fn save_meeting_note(dir: &Path, created: &chrono::DateTime<chrono::Local>, markdown: &str) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let path = dir.join(format!("meeting-{}.md", created.format("%Y-%m-%d-%H%M%S")));
+    std::fs::write(&path, markdown)
+        .map_err(|e| format!("Failed to save meeting notes: {}", e))?;
+
+    Ok(path)
+}
+
+#[tauri::command]
+pub async fn generate_meeting_notes(app_handle: tauri::AppHandle, max_speakers: Option<usize>) -> Result<(), String> {
+    // Fail fast before opening a window if there is nothing to work with
+    let tracks = crate::last_recording()?;
+
+    let api_key = credentials::api_key(&app_handle)?;
+
+    let window = tauri::WebviewWindowBuilder::new(
+        &app_handle,
+        "meeting-notes",
+        tauri::WebviewUrl::App("meeting-notes.html".into())
+    )
+    .title("Meeting Notes")
+    .inner_size(800.0, 650.0)
+    .center()
+    .resizable(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    // Wait for window to load before sending events
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+
+    match run_pipeline(&app_handle, &window, &api_key, tracks, max_speakers).await {
+        Ok(result) => {
+            println!("🎉 MEETING NOTES SUCCESS: saved to {}", result.path);
+            let _ = window.emit("meeting-notes-complete", &result);
+            Ok(())
+        }
+        Err(e) => {
+            println!("❌ Meeting notes failed: {}", e);
+            let _ = window.emit("meeting-notes-error", &e);
+            Err(e)
+        }
+    }
+}
+
+async fn run_pipeline(
+    app_handle: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+    api_key: &str,
+    tracks: crate::RecordedTracks,
+    max_speakers: Option<usize>,
+) -> Result<MeetingNotesResult, String> {
+    let created = chrono::Local::now();
+
+    let _ = window.emit("meeting-notes-status", "transcribing");
+    let settings = settings::current(app_handle);
+    let segments = crate::transcribe_tracks(api_key, &tracks, max_speakers, &settings.audio_processing).await?;
+    let transcript = diarization::format_speaker_transcript(&segments, &tracks.pauses);
+    let _ = window.emit("meeting-notes-transcript", &transcript);
+
+    let _ = window.emit("meeting-notes-status", "writing");
+    println!("📝 Writing meeting notes from {} transcript segments...", segments.len());
+
+    let payload = serde_json::json!({
+        "model": settings.model,
+        "messages": [
+            {
+                "role": "system",
+                "content": MEETING_NOTES_PROMPT
+            },
+            {
+                "role": "user",
+                "content": transcript
+            }
+        ],
+        "temperature": settings.temperature,
+        "max_tokens": 1500,
+        "stream": true
+    });
+
+    let notes = crate::stream_chat_completion(api_key, payload, |chunk| {
+        let _ = window.emit("meeting-notes-chunk", chunk);
+    })
+    .await?;
+
+    let markdown = render_meeting_note(&created, &notes, &transcript);
+    let notes_dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join("meeting-notes");
+    let path = save_meeting_note(&notes_dir, &created, &markdown)?;
+
+    Ok(MeetingNotesResult {
+        markdown,
+        path: path.to_string_lossy().to_string(),
+    })
+}
@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+
+use crate::diarization::AudioSource;
+
+/// Peak amplitude below which a capture counts as silent (about -60 dBFS).
+pub const SILENCE_THRESHOLD: f32 = 0.001;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ChannelLevel {
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// Payload of the periodic `audio-level` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioLevel {
+    pub source: AudioSource,
+    pub channels: Vec<ChannelLevel>,
+    pub elapsed_seconds: f32,
+    pub bytes_captured: u64,
+}
+
+impl AudioLevel {
+    pub fn peak(&self) -> f32 {
+        self.channels.iter().map(|level| level.peak).fold(0.0, f32::max)
+    }
+}
+
+/// Payload of the `audio-silence-warning` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SilenceWarning {
+    pub source: AudioSource,
+    pub silent_seconds: f32,
+}
+
+/// Accumulates per-channel levels from the capture callback between two reports.
+#[derive(Debug, Clone)]
+pub struct LevelMeter {
+    sum_squares: Vec<f64>,
+    peaks: Vec<f32>,
+    frames: u64,
+    total_samples: u64,
+}
+
+impl LevelMeter {
+    pub fn new(channels: u16) -> Self {
+        let channels = channels.max(1) as usize;
+        LevelMeter {
+            sum_squares: vec![0.0; channels],
+            peaks: vec![0.0; channels],
+            frames: 0,
+            total_samples: 0,
+        }
+    }
+
+    pub fn process(&mut self, data: &[f32]) {
+        let channels = self.peaks.len();
+        for frame in data.chunks(channels) {
+            for (channel, &sample) in frame.iter().enumerate() {
+                self.sum_squares[channel] += (sample as f64) * (sample as f64);
+                self.peaks[channel] = self.peaks[channel].max(sample.abs());
+            }
+            self.frames += 1;
+        }
+        self.total_samples += data.len() as u64;
+    }
+
+    /// Returns the levels since the previous call and starts a new measurement window.
+    pub fn take_levels(&mut self) -> Vec<ChannelLevel> {
+        let frames = self.frames.max(1) as f64;
+        let levels = self.sum_squares
+            .iter()
+            .zip(&self.peaks)
+            .map(|(&sum, &peak)| ChannelLevel {
+                rms: (sum / frames).sqrt() as f32,
+                peak,
+            })
+            .collect();
+
+        self.sum_squares.iter_mut().for_each(|sum| *sum = 0.0);
+        self.peaks.iter_mut().for_each(|peak| *peak = 0.0);
+        self.frames = 0;
+        levels
+    }
+
+    pub fn bytes_captured(&self) -> u64 {
+        self.total_samples * std::mem::size_of::<f32>() as u64
+    }
+}
+
+/// Tracks how long a capture has been silent and fires once per silent stretch.
+#[derive(Debug, Clone)]
+pub struct SilenceDetector {
+    warn_after_seconds: f32,
+    silent_seconds: f32,
+    warned: bool,
+}
+
+impl SilenceDetector {
+    pub fn new(warn_after_seconds: f32) -> Self {
+        SilenceDetector {
+            warn_after_seconds,
+            silent_seconds: 0.0,
+            warned: false,
+        }
+    }
+
+    /// Feeds the peak of the last interval. Returns the silent duration when a warning is due.
+    pub fn update(&mut self, peak: f32, interval_seconds: f32) -> Option<f32> {
+        if peak >= SILENCE_THRESHOLD {
+            self.silent_seconds = 0.0;
+            self.warned = false;
+            return None;
+        }
+
+        self.silent_seconds += interval_seconds;
+        if !self.warned && self.silent_seconds >= self.warn_after_seconds {
+            self.warned = true;
+            return Some(self.silent_seconds);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 48_000;
+
+    fn sine(frequency: f32, amplitude: f32, seconds: f32) -> Vec<f32> {
+        (0..(RATE as f32 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn measure(channels: u16, samples: &[f32]) -> Vec<ChannelLevel> {
+        let mut meter = LevelMeter::new(channels);
+        meter.process(samples);
+        meter.take_levels()
+    }
+
+    #[test]
+    fn silence_has_no_level() {
+        let levels = measure(1, &vec![0.0; RATE as usize]);
+        assert_eq!(levels[0].rms, 0.0);
+        assert_eq!(levels[0].peak, 0.0);
+    }
+
+    #[test]
+    fn full_scale_sine_peaks_at_one_with_rms_of_one_over_root_two() {
+        let levels = measure(1, &sine(1000.0, 1.0, 1.0));
+        assert!((levels[0].peak - 1.0).abs() < 1e-3, "peak was {}", levels[0].peak);
+        assert!((levels[0].rms - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3, "rms was {}", levels[0].rms);
+    }
+
+    #[test]
+    fn clipped_signal_reports_full_scale() {
+        // A sine driven to twice full scale and clipped sits at ±1 for two thirds of the time,
+        // which raises its RMS from 0.707 to sqrt(2/3 + (2π/3 - √3) / π) ≈ 0.884
+        let clipped: Vec<f32> = sine(1000.0, 2.0, 1.0).into_iter().map(|s| s.clamp(-1.0, 1.0)).collect();
+        let levels = measure(1, &clipped);
+        assert_eq!(levels[0].peak, 1.0);
+        assert!((levels[0].rms - 0.884).abs() < 5e-3, "rms was {}", levels[0].rms);
+
+        // Out-of-range float samples are reported as they are, not capped
+        assert_eq!(measure(1, &[0.5, -1.5, 0.25])[0].peak, 1.5);
+    }
+
+    #[test]
+    fn measures_interleaved_channels_separately() {
+        let left = sine(440.0, 0.5, 0.5);
+        let interleaved: Vec<f32> = left.iter().flat_map(|&s| [s, 0.0]).collect();
+        let levels = measure(2, &interleaved);
+        assert!((levels[0].peak - 0.5).abs() < 1e-3);
+        assert!((levels[0].rms - 0.5 * std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        assert_eq!(levels[1].peak, 0.0);
+        assert_eq!(levels[1].rms, 0.0);
+    }
+
+    #[test]
+    fn take_levels_starts_a_new_window() {
+        let mut meter = LevelMeter::new(1);
+        meter.process(&[0.8, -0.8]);
+        assert_eq!(meter.take_levels()[0].peak, 0.8);
+        meter.process(&[0.1, -0.1]);
+        assert_eq!(meter.take_levels()[0].peak, 0.1);
+        assert_eq!(meter.take_levels()[0].rms, 0.0);
+        assert_eq!(meter.bytes_captured(), 4 * std::mem::size_of::<f32>() as u64);
+    }
+
+    #[test]
+    fn silence_detector_warns_once_per_silent_stretch() {
+        let mut detector = SilenceDetector::new(1.0);
+        assert_eq!(detector.update(0.0, 0.5), None);
+        assert_eq!(detector.update(0.0, 0.5), Some(1.0));
+        assert_eq!(detector.update(0.0, 0.5), None);
+        assert_eq!(detector.update(0.5, 0.5), None);
+        assert_eq!(detector.update(0.0, 0.5), None);
+        assert_eq!(detector.update(0.0, 0.5), Some(1.0));
+    }
+}
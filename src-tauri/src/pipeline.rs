@@ -0,0 +1,543 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::Emitter;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::prompts::{self, PromptTemplate, RenderedPrompt};
+use crate::summary::SummaryOptions;
+use crate::{credentials, custom_agents, diarization, glossary, language, settings, style, summary};
+
+pub const MAX_STEPS: usize = 8;
+
+/// One stage of a pipeline; it receives the output of the previous step as its input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PipelineStep {
+    /// Transcript of the last recording; only allowed as the first step
+    Transcribe,
+    /// Into `target_language`, or the settings' target language when unset
+    Translate {
+        #[serde(default)]
+        target_language: Option<String>,
+    },
+    /// With these options, or the saved summary settings when unset
+    Summarize {
+        #[serde(default)]
+        options: Option<SummaryOptions>,
+    },
+    /// A prompt of its own; `{{text}}` is the input, `{{target_language}}` and `{{tone}}` work as in the built-in templates
+    Prompt {
+        prompt: PromptTemplate,
+        #[serde(default)]
+        model: Option<String>,
+    },
+    /// A custom agent from the agents folder, by id; its input source and output sink are ignored
+    Agent { id: String },
+}
+
+impl PipelineStep {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            PipelineStep::Transcribe => Ok(()),
+            PipelineStep::Translate { target_language } => {
+                if target_language.as_deref().is_some_and(|language| language.trim().is_empty()) {
+                    return Err("target_language must not be empty".to_string());
+                }
+                Ok(())
+            }
+            PipelineStep::Summarize { options } => options.as_ref().map_or(Ok(()), SummaryOptions::validate),
+            PipelineStep::Prompt { prompt, model } => {
+                prompt.validate()?;
+                if model.as_deref().is_some_and(|model| model.trim().is_empty()) {
+                    return Err("model must not be empty".to_string());
+                }
+                Ok(())
+            }
+            PipelineStep::Agent { id } => {
+                if id.trim().is_empty() {
+                    return Err("agent id must not be empty".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Short description shown in the pipeline window.
+    pub fn label(&self, settings: &settings::Settings) -> String {
+        match self {
+            PipelineStep::Transcribe => "Transcribe".to_string(),
+            PipelineStep::Translate { target_language } => {
+                format!("Translate to {}", target_language.as_deref().unwrap_or(&settings.target_language))
+            }
+            PipelineStep::Summarize { .. } => "Summarize".to_string(),
+            PipelineStep::Prompt { .. } => "Custom prompt".to_string(),
+            PipelineStep::Agent { id } => format!("Agent '{}'", id),
+        }
+    }
+}
+
+/// An ordered list of steps, saved under an id in the `pipelines` section of the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pipeline {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Global shortcut such as "cmd+shift+m"; pipelines without one are run from code
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    pub steps: Vec<PipelineStep>,
+}
+
+impl Pipeline {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        if self.shortcut.as_deref().is_some_and(|shortcut| shortcut.trim().is_empty()) {
+            return Err("shortcut must not be empty".to_string());
+        }
+        if self.steps.is_empty() || self.steps.len() > MAX_STEPS {
+            return Err(format!("a pipeline needs between 1 and {} steps", MAX_STEPS));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            if index > 0 && *step == PipelineStep::Transcribe {
+                return Err(format!("step {}: transcribe can only be the first step", index + 1));
+            }
+            step.validate().map_err(|e| format!("step {}: {}", index + 1, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// The pipelines every installation starts with.
+pub fn defaults() -> BTreeMap<String, Pipeline> {
+    BTreeMap::from([
+        ("meeting-summary-en".to_string(), Pipeline {
+            name: "Meeting summary in English".to_string(),
+            description: "Transcribes the last recording, translates it to English and summarizes it".to_string(),
+            shortcut: None,
+            steps: vec![
+                PipelineStep::Transcribe,
+                PipelineStep::Translate { target_language: Some("English".to_string()) },
+                PipelineStep::Summarize { options: None },
+            ],
+        }),
+        ("summarize-translate".to_string(), Pipeline {
+            name: "Summarize and translate".to_string(),
+            description: "Summarizes the clipboard and translates the summary to the target language".to_string(),
+            shortcut: None,
+            steps: vec![
+                PipelineStep::Summarize { options: None },
+                PipelineStep::Translate { target_language: None },
+            ],
+        }),
+    ])
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PipelineRequest {
+    /// Id of a pipeline saved in the settings
+    pub pipeline: Option<String>,
+    /// Steps to run instead of a saved pipeline
+    pub steps: Option<Vec<PipelineStep>>,
+    /// Input of the first step; the clipboard when omitted, unused when the first step transcribes
+    pub input: Option<String>,
+    pub credential: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepOutput {
+    pub index: usize,
+    pub label: String,
+    pub output: String,
+    /// Why the step passed its input through unchanged, e.g. a text already in the target language
+    pub note: Option<String>,
+    /// Model that produced the output; none when the step passed its input through
+    pub model: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepFailure {
+    pub index: usize,
+    pub label: String,
+    pub error: String,
+}
+
+/// Outputs of the steps that ran; a failed step stops the pipeline but keeps the earlier results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRun {
+    pub pipeline: String,
+    pub steps: Vec<StepOutput>,
+    /// Output of the last step, when every step succeeded
+    pub output: Option<String>,
+    pub failure: Option<StepFailure>,
+    pub credential: String,
+    /// Model every step that ran used; none when the steps used different models
+    pub model: Option<String>,
+}
+
+impl PipelineRun {
+    fn new(pipeline: String, steps: Vec<StepOutput>, failure: Option<StepFailure>, credential: String) -> Self {
+        let mut models = steps.iter().filter_map(|step| step.model.as_deref());
+        let first = models.next();
+        let model = first.filter(|first| models.all(|model| model == *first)).map(str::to_string);
+        PipelineRun {
+            pipeline,
+            output: steps.last().filter(|_| failure.is_none()).map(|step| step.output.clone()),
+            steps,
+            failure,
+            credential,
+            model,
+        }
+    }
+}
+
+/// Payload of `pipeline-info`, sent before the first step starts. Models are reported per step
+/// as each one completes, since agents and prompt steps may pick their own.
+#[derive(Debug, Clone, Serialize)]
+struct PipelineInfo<'a> {
+    name: &'a str,
+    steps: Vec<String>,
+    credential: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StepChunk<'a> {
+    index: usize,
+    chunk: &'a str,
+}
+
+struct StepResult {
+    output: String,
+    note: Option<String>,
+    model: Option<String>,
+}
+
+impl StepResult {
+    fn new(output: String, model: &str) -> Self {
+        StepResult { output, note: None, model: Some(model.to_string()) }
+    }
+}
+
+fn emit<S: Serialize + Clone>(window: Option<&tauri::WebviewWindow>, event: &str, payload: S) {
+    if let Some(window) = window {
+        let _ = window.emit(event, payload);
+    }
+}
+
+/// Response budget for steps whose output is about as long as their input.
+fn length_budget(text: &str) -> u32 {
+    (text.chars().count() / 2 + 200).min(4000) as u32
+}
+
+fn chat_payload(model: &str, temperature: f32, prompt: &RenderedPrompt, max_tokens: u32) -> serde_json::Value {
+    serde_json::json!({
+        "model": model,
+        "messages": [
+            {
+                "role": "system",
+                "content": prompt.system
+            },
+            {
+                "role": "user",
+                "content": prompt.user
+            }
+        ],
+        "temperature": temperature,
+        "max_tokens": max_tokens,
+        "stream": true
+    })
+}
+
+async fn run_step<F: FnMut(&str)>(
+    app_handle: &tauri::AppHandle,
+    settings: &settings::Settings,
+    api_key: &str,
+    step: &PipelineStep,
+    input: &str,
+    on_chunk: F,
+) -> Result<StepResult, String> {
+    let result: StepResult = match step {
+        PipelineStep::Transcribe => {
+            let tracks = crate::last_recording()?;
+            let segments = crate::transcribe_tracks(api_key, &tracks, None, &settings.audio_processing).await?;
+            StepResult::new(diarization::format_speaker_transcript(&segments, &tracks.pauses), crate::TRANSCRIPTION_MODEL)
+        }
+        PipelineStep::Translate { target_language } => {
+            let target_language = target_language.as_deref().unwrap_or(&settings.target_language);
+            // Unlike the translator, a pipeline never switches to the alternate language
+            if let Some(source) = language::detect(input).filter(|source| source.reliable && source.is(target_language)) {
+                println!("🌍 Pipeline input is already in {}, passing it on", source.name);
+                return Ok(StepResult {
+                    output: input.to_string(),
+                    note: Some(format!("Already in {}", source.name)),
+                    model: None,
+                });
+            }
+            let request = crate::TranslationRequest {
+                text: input.to_string(),
+                target_language: target_language.to_string(),
+                credential: None,
+                format: None,
+                style: Default::default(),
+                verify: Some(false),
+            };
+            let style = settings.translation_styles.get(&style::language_key(target_language)).cloned().unwrap_or_default();
+            let glossary = glossary::load(app_handle);
+            let prompt = crate::translation_prompt(settings, &glossary, &request, &style, None);
+            let payload = chat_payload(&settings.model, settings.temperature, &prompt, length_budget(input));
+            let translation = crate::stream_chat_completion(api_key, payload, on_chunk).await?;
+            crate::check_glossary(&glossary, &request, &translation);
+            StepResult::new(translation, &settings.model)
+        }
+        PipelineStep::Summarize { options } => {
+            let options = options.clone().unwrap_or_else(|| settings.summary.clone());
+            let prompt = summary::build_prompt(settings, input, &options);
+            let payload = chat_payload(&settings.model, settings.temperature, &prompt, options.max_tokens());
+            StepResult::new(crate::stream_chat_completion(api_key, payload, on_chunk).await?, &settings.model)
+        }
+        PipelineStep::Prompt { prompt, model } => {
+            let prompt = prompt.render(&HashMap::from([
+                ("text".to_string(), input.to_string()),
+                ("target_language".to_string(), settings.target_language.clone()),
+                ("tone".to_string(), prompts::DEFAULT_TONE.to_string()),
+            ]));
+            let model = model.as_deref().unwrap_or(&settings.model);
+            let payload = chat_payload(model, settings.temperature, &prompt, length_budget(input));
+            StepResult::new(crate::stream_chat_completion(api_key, payload, on_chunk).await?, model)
+        }
+        PipelineStep::Agent { id } => {
+            let agent = custom_agents::find(app_handle, id)?;
+            let output = custom_agents::execute(app_handle, &agent, api_key, input, on_chunk).await?;
+            StepResult::new(output, agent.model.as_deref().unwrap_or(&settings.model))
+        }
+    };
+
+    let output = result.output.trim().to_string();
+    if output.is_empty() {
+        return Err("Step produced no output".to_string());
+    }
+    Ok(StepResult { output, ..result })
+}
+
+/// Runs the steps in order, feeding each output into the next step. Progress is streamed into
+/// `window` when given; a failing step ends the run.
+async fn run(
+    app_handle: &tauri::AppHandle,
+    settings: &settings::Settings,
+    api_key: &str,
+    pipeline: &Pipeline,
+    input: String,
+    window: Option<&tauri::WebviewWindow>,
+) -> (Vec<StepOutput>, Option<StepFailure>) {
+    let mut steps = Vec::new();
+    let mut input = input;
+    for (index, step) in pipeline.steps.iter().enumerate() {
+        let label = step.label(settings);
+        println!("🔗 Pipeline '{}' step {}/{}: {}", pipeline.name, index + 1, pipeline.steps.len(), label);
+        emit(window, "pipeline-step-started", index);
+
+        let start_time = std::time::Instant::now();
+        let on_chunk = |chunk: &str| emit(window, "pipeline-step-chunk", StepChunk { index, chunk });
+        match run_step(app_handle, settings, api_key, step, &input, on_chunk).await {
+            Ok(result) => {
+                let output = StepOutput {
+                    index,
+                    label,
+                    output: result.output,
+                    note: result.note,
+                    model: result.model,
+                    duration_ms: start_time.elapsed().as_millis() as u64,
+                };
+                emit(window, "pipeline-step-complete", &output);
+                input = output.output.clone();
+                steps.push(output);
+            }
+            Err(e) => {
+                println!("❌ Pipeline '{}' failed at step {}: {}", pipeline.name, index + 1, e);
+                let failure = StepFailure { index, label, error: e };
+                emit(window, "pipeline-step-error", &failure);
+                return (steps, Some(failure));
+            }
+        }
+    }
+    println!("🎉 Pipeline '{}' finished {} step(s)", pipeline.name, steps.len());
+    (steps, None)
+}
+
+/// Picks the pipeline to run and its input, before any window is opened.
+fn prepare(app_handle: &tauri::AppHandle, settings: &settings::Settings, request: PipelineRequest) -> Result<(Pipeline, String), String> {
+    let pipeline = match (request.steps, request.pipeline) {
+        (Some(steps), _) => Pipeline {
+            name: "Pipeline".to_string(),
+            description: String::new(),
+            shortcut: None,
+            steps,
+        },
+        (None, Some(id)) => settings.pipelines.get(&id).cloned()
+            .ok_or_else(|| format!("No pipeline '{}' in the settings", id))?,
+        (None, None) => return Err("Name a saved pipeline or give the steps to run".to_string()),
+    };
+    pipeline.validate()?;
+
+    let input = match (&pipeline.steps[0], request.input) {
+        (PipelineStep::Transcribe, _) => String::new(),
+        (_, Some(input)) => input,
+        (_, None) => app_handle.clipboard().read_text()
+            .map_err(|e| format!("Failed to read clipboard: {}", e))?,
+    };
+    if pipeline.steps[0] != PipelineStep::Transcribe && input.trim().is_empty() {
+        return Err(format!("No input for '{}'", pipeline.name));
+    }
+    Ok((pipeline, input))
+}
+
+#[tauri::command]
+pub async fn run_pipeline(app_handle: tauri::AppHandle, request: PipelineRequest) -> Result<PipelineRun, String> {
+    let credential = credentials::resolve(&app_handle, request.credential.as_deref())?;
+    let settings = settings::current(&app_handle);
+    let (pipeline, input) = prepare(&app_handle, &settings, request)?;
+
+    let (steps, failure) = run(&app_handle, &settings, &credential.api_key, &pipeline, input, None).await;
+    Ok(PipelineRun::new(pipeline.name, steps, failure, credential.name))
+}
+
+/// Opens the pipeline window and runs the pipeline in it, showing every step's output as it streams.
+#[tauri::command]
+pub async fn show_pipeline_window(app_handle: tauri::AppHandle, request: PipelineRequest) -> Result<(), String> {
+    // Fail fast before opening a window without a usable key or input
+    let credential = credentials::resolve(&app_handle, request.credential.as_deref())?;
+    let settings = settings::current(&app_handle);
+    let (pipeline, input) = prepare(&app_handle, &settings, request)?;
+
+    let window = tauri::WebviewWindowBuilder::new(
+        &app_handle,
+        "pipeline",
+        tauri::WebviewUrl::App("pipeline.html".into())
+    )
+    .title(&pipeline.name)
+    .inner_size(750.0, 650.0)
+    .center()
+    .resizable(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    // Wait for window to load before sending events
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+
+    let _ = window.emit("pipeline-info", PipelineInfo {
+        name: &pipeline.name,
+        steps: pipeline.steps.iter().map(|step| step.label(&settings)).collect(),
+        credential: &credential.name,
+    });
+    let _ = window.emit("pipeline-input", &input);
+
+    let (steps, failure) = run(&app_handle, &settings, &credential.api_key, &pipeline, input, Some(&window)).await;
+    let _ = window.emit("pipeline-complete", &PipelineRun::new(pipeline.name, steps, failure, credential.name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(steps: Vec<PipelineStep>) -> Pipeline {
+        Pipeline {
+            name: "Test".to_string(),
+            description: String::new(),
+            shortcut: None,
+            steps,
+        }
+    }
+
+    fn translate() -> PipelineStep {
+        PipelineStep::Translate { target_language: None }
+    }
+
+    fn step(index: usize, model: Option<&str>) -> StepOutput {
+        StepOutput {
+            index,
+            label: format!("Step {}", index + 1),
+            output: format!("output {}", index + 1),
+            note: None,
+            model: model.map(str::to_string),
+            duration_ms: 10,
+        }
+    }
+
+    #[test]
+    fn default_pipelines_are_valid() {
+        for (id, pipeline) in defaults() {
+            assert!(pipeline.validate().is_ok(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn transcribe_is_only_allowed_first() {
+        assert!(pipeline(vec![PipelineStep::Transcribe, translate()]).validate().is_ok());
+        assert!(pipeline(vec![PipelineStep::Transcribe]).validate().is_ok());
+
+        let error = pipeline(vec![translate(), PipelineStep::Transcribe]).validate().unwrap_err();
+        assert_eq!(error, "step 2: transcribe can only be the first step");
+        assert!(pipeline(vec![PipelineStep::Transcribe, PipelineStep::Transcribe]).validate().is_err());
+    }
+
+    #[test]
+    fn step_count_is_limited() {
+        let expected = format!("a pipeline needs between 1 and {} steps", MAX_STEPS);
+        assert_eq!(pipeline(Vec::new()).validate().unwrap_err(), expected);
+        assert!(pipeline(vec![translate(); MAX_STEPS]).validate().is_ok());
+        assert_eq!(pipeline(vec![translate(); MAX_STEPS + 1]).validate().unwrap_err(), expected);
+    }
+
+    #[test]
+    fn step_errors_name_the_step() {
+        let steps = vec![
+            translate(),
+            PipelineStep::Prompt {
+                prompt: PromptTemplate { system: "Rewrite this.".to_string(), user: "{{text}}".to_string() },
+                model: Some(" ".to_string()),
+            },
+        ];
+        assert_eq!(pipeline(steps).validate().unwrap_err(), "step 2: model must not be empty");
+
+        let steps = vec![PipelineStep::Translate { target_language: Some(String::new()) }];
+        assert_eq!(pipeline(steps).validate().unwrap_err(), "step 1: target_language must not be empty");
+
+        let steps = vec![PipelineStep::Agent { id: String::new() }];
+        assert_eq!(pipeline(steps).validate().unwrap_err(), "step 1: agent id must not be empty");
+    }
+
+    #[test]
+    fn name_and_shortcut_must_not_be_blank() {
+        let mut unnamed = pipeline(vec![translate()]);
+        unnamed.name = "  ".to_string();
+        assert_eq!(unnamed.validate().unwrap_err(), "name must not be empty");
+
+        let mut blank_shortcut = pipeline(vec![translate()]);
+        blank_shortcut.shortcut = Some(String::new());
+        assert_eq!(blank_shortcut.validate().unwrap_err(), "shortcut must not be empty");
+    }
+
+    #[test]
+    fn run_reports_a_model_only_when_every_step_used_it() {
+        let run = PipelineRun::new("Test".to_string(), vec![step(0, Some("gpt-4o")), step(1, None), step(2, Some("gpt-4o"))], None, "default".to_string());
+        assert_eq!(run.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(run.output.as_deref(), Some("output 3"));
+
+        let run = PipelineRun::new("Test".to_string(), vec![step(0, Some("whisper-1")), step(1, Some("gpt-4o"))], None, "default".to_string());
+        assert_eq!(run.model, None);
+        assert_eq!(run.steps[0].model.as_deref(), Some("whisper-1"));
+        assert_eq!(run.steps[1].model.as_deref(), Some("gpt-4o"));
+    }
+
+    #[test]
+    fn failed_runs_keep_earlier_outputs_but_have_no_result() {
+        let failure = StepFailure { index: 1, label: "Step 2".to_string(), error: "Step produced no output".to_string() };
+        let run = PipelineRun::new("Test".to_string(), vec![step(0, Some("gpt-4o"))], Some(failure), "default".to_string());
+        assert_eq!(run.output, None);
+        assert_eq!(run.steps.len(), 1);
+        assert_eq!(run.model.as_deref(), Some("gpt-4o"));
+    }
+}
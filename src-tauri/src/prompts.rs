@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::settings::{self, Settings};
+
+/// Variables a template may reference as `{{name}}`.
+pub const KNOWN_VARIABLES: &[&str] = &["target_language", "text", "tone"];
+
+/// Tone used until a request or setting asks for another one.
+pub const DEFAULT_TONE: &str = "neutral";
+
+/// System and user message for one LLM feature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub system: String,
+    pub user: String,
+}
+
+/// The editable templates, stored in the `prompts` section of the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptTemplates {
+    pub translate: PromptTemplate,
+    pub summarize: PromptTemplate,
+}
+
+impl Default for PromptTemplates {
+    fn default() -> Self {
+        PromptTemplates {
+            translate: PromptTemplate {
+                system: "You are a professional translator. Translate the given text to {{target_language}}. Only respond with the translation, no explanations or additional text.".to_string(),
+                user: "{{text}}".to_string(),
+            },
+            summarize: PromptTemplate {
+                system: "You are an expert text summarizer. Create a clear, concise summary that captures the key points and main ideas. Keep it informative but brief. Focus on the most important information.".to_string(),
+                user: "Please summarize the following text:\n\n{{text}}".to_string(),
+            },
+        }
+    }
+}
+
+impl PromptTemplates {
+    pub fn validate(&self) -> Result<(), String> {
+        self.translate.validate().map_err(|e| format!("prompts.translate: {}", e))?;
+        self.summarize.validate().map_err(|e| format!("prompts.summarize: {}", e))
+    }
+
+    pub fn get(&self, kind: PromptKind) -> &PromptTemplate {
+        match kind {
+            PromptKind::Translate => &self.translate,
+            PromptKind::Summarize => &self.summarize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptKind {
+    Translate,
+    Summarize,
+}
+
+impl PromptTemplate {
+    /// Rejects unknown variables and unterminated `{{`, and makes sure the text is sent at all.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut used = variables(&self.system).map_err(|e| format!("system: {}", e))?;
+        used.extend(variables(&self.user).map_err(|e| format!("user: {}", e))?);
+
+        let unknown: Vec<&str> = used.iter()
+            .map(String::as_str)
+            .filter(|name| !KNOWN_VARIABLES.contains(name))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "unknown variable(s) {} (available: {})",
+                unknown.iter().map(|name| format!("{{{{{}}}}}", name)).collect::<Vec<_>>().join(", "),
+                KNOWN_VARIABLES.join(", ")
+            ));
+        }
+        if !used.iter().any(|name| name == "text") {
+            return Err("template must contain {{text}}".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn render(&self, variables: &HashMap<String, String>) -> RenderedPrompt {
+        RenderedPrompt {
+            system: render(&self.system, variables),
+            user: render(&self.user, variables),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedPrompt {
+    pub system: String,
+    pub user: String,
+}
+
+/// Names of the `{{name}}` placeholders in `template`, in order of appearance.
+fn variables(template: &str) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| format!("unterminated '{{{{' at \"{}\"", &rest[start..].chars().take(20).collect::<String>()))?;
+        names.push(after[..end].trim().to_string());
+        rest = &after[end + 2..];
+    }
+    Ok(names)
+}
+
+/// Substitutes every placeholder; variables without a value render as an empty string.
+fn render(template: &str, variables: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                if let Some(value) = variables.get(after[..end].trim()) {
+                    output.push_str(value);
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Renders the configured template for `kind`, filling `tone` with the default when not given.
+pub fn render_for(settings: &Settings, kind: PromptKind, mut variables: HashMap<String, String>) -> RenderedPrompt {
+    variables.entry("tone".to_string()).or_insert_with(|| DEFAULT_TONE.to_string());
+    settings.prompts.get(kind).render(&variables)
+}
+
+/// Previews a template: the stored one for `kind`, or `template` when the user is still editing it.
+/// Missing variables fall back to the current target language, the default tone and sample text.
+#[tauri::command]
+pub async fn render_prompt(
+    app_handle: tauri::AppHandle,
+    kind: PromptKind,
+    template: Option<PromptTemplate>,
+    variables: Option<HashMap<String, String>>,
+) -> Result<RenderedPrompt, String> {
+    let settings = settings::current(&app_handle);
+    let template = template.unwrap_or_else(|| settings.prompts.get(kind).clone());
+    template.validate()?;
+
+    let mut variables = variables.unwrap_or_default();
+    if let Some(name) = variables.keys().find(|name| !KNOWN_VARIABLES.contains(&name.as_str())) {
+        return Err(format!("Unknown variable '{}' (available: {})", name, KNOWN_VARIABLES.join(", ")));
+    }
+    variables.entry("target_language".to_string()).or_insert(settings.target_language);
+    variables.entry("tone".to_string()).or_insert_with(|| DEFAULT_TONE.to_string());
+    variables.entry("text".to_string()).or_insert_with(|| "The quick brown fox jumps over the lazy dog.".to_string());
+    Ok(template.render(&variables))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(system: &str, user: &str) -> PromptTemplate {
+        PromptTemplate { system: system.to_string(), user: user.to_string() }
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn default_templates_are_valid() {
+        assert!(PromptTemplates::default().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_accepts_known_variables_anywhere() {
+        assert!(template("Translate to {{ target_language }} in a {{tone}} tone.", "{{text}}").validate().is_ok());
+        assert!(template("{{text}}", "").validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_variables() {
+        let error = template("Write like {{author}}.", "{{text}} {{ mood }}").validate().unwrap_err();
+        assert!(error.contains("{{author}}"), "{}", error);
+        assert!(error.contains("{{mood}}"), "{}", error);
+        assert!(error.contains("target_language, text, tone"), "{}", error);
+    }
+
+    #[test]
+    fn validate_requires_text() {
+        let error = template("Translate to {{target_language}}.", "Please translate.").validate().unwrap_err();
+        assert_eq!(error, "template must contain {{text}}");
+    }
+
+    #[test]
+    fn validate_rejects_unterminated_placeholders() {
+        let error = template("Translate to {{target_language.", "{{text}}").validate().unwrap_err();
+        assert!(error.starts_with("system: unterminated"), "{}", error);
+
+        let error = template("", "{{text}} and {{tone").validate().unwrap_err();
+        assert!(error.starts_with("user: unterminated"), "{}", error);
+    }
+
+    #[test]
+    fn validate_names_the_failing_template() {
+        let mut templates = PromptTemplates::default();
+        templates.summarize.user = "Summarize this.".to_string();
+        assert_eq!(templates.validate().unwrap_err(), "prompts.summarize: template must contain {{text}}");
+    }
+
+    #[test]
+    fn render_substitutes_every_occurrence() {
+        let rendered = template("Translate to {{target_language}}; keep it {{ tone }}.", "{{text}}\n\n{{text}}")
+            .render(&values(&[("target_language", "German"), ("tone", "formal"), ("text", "Hallo")]));
+        assert_eq!(rendered.system, "Translate to German; keep it formal.");
+        assert_eq!(rendered.user, "Hallo\n\nHallo");
+    }
+
+    #[test]
+    fn render_leaves_missing_variables_empty_and_values_unexpanded() {
+        // A value that itself looks like a placeholder is inserted verbatim, not rendered again
+        let rendered = template("Tone: {{tone}}.", "{{text}}").render(&values(&[("text", "literal {{tone}}")]));
+        assert_eq!(rendered.system, "Tone: .");
+        assert_eq!(rendered.user, "literal {{tone}}");
+    }
+
+    #[test]
+    fn render_for_fills_the_default_tone() {
+        let mut settings = Settings::default();
+        settings.prompts.summarize = template("Be {{tone}}.", "{{text}}");
+        let rendered = render_for(&settings, PromptKind::Summarize, values(&[("text", "Notes")]));
+        assert_eq!(rendered.system, format!("Be {}.", DEFAULT_TONE));
+        assert_eq!(rendered.user, "Notes");
+
+        let rendered = render_for(&settings, PromptKind::Summarize, values(&[("text", "Notes"), ("tone", "casual")]));
+        assert_eq!(rendered.system, "Be casual.");
+    }
+}
@@ -0,0 +1,252 @@
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::OnceLock;
+
+use crate::CaptureFormat;
+
+/// A PulseAudio (or PipeWire via pipewire-pulse) monitor source, i.e. the loopback of an output sink.
+#[derive(Debug, Clone)]
+pub struct MonitorSource {
+    pub name: String,
+    pub format: CaptureFormat,
+}
+
+const MISSING_TOOLS: &str = "install pulseaudio-utils (pactl and parec) to record system audio on Linux";
+
+/// Whether `pactl` can be started at all. Checked once, so a system without pulseaudio-utils
+/// costs one failed spawn instead of one per device poll.
+fn pactl_installed() -> bool {
+    static INSTALLED: OnceLock<bool> = OnceLock::new();
+    *INSTALLED.get_or_init(|| {
+        let status = Command::new("pactl")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if let Err(e) = &status {
+            println!("⚠️ pactl not available ({}): {}", e, MISSING_TOOLS);
+        }
+        status.is_ok()
+    })
+}
+
+/// Runs `pactl` with a fixed locale so its output can be parsed.
+fn pactl(args: &[&str]) -> Result<String, String> {
+    if !pactl_installed() {
+        return Err(format!("pactl not found: {}", MISSING_TOOLS));
+    }
+    let output = Command::new("pactl")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| format!("Failed to run pactl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("pactl {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses a sample spec such as `s16le 2ch 44100Hz`.
+fn parse_sample_spec(spec: &str) -> Option<CaptureFormat> {
+    let mut channels = None;
+    let mut sample_rate = None;
+    for part in spec.split_whitespace() {
+        if let Some(value) = part.strip_suffix("ch") {
+            channels = value.parse().ok();
+        } else if let Some(value) = part.strip_suffix("Hz") {
+            sample_rate = value.parse().ok();
+        }
+    }
+    Some(CaptureFormat {
+        sample_rate: sample_rate?,
+        channels: channels?,
+    })
+}
+
+/// Monitor sources in the output of `pactl list short sources`.
+fn parse_monitor_sources(output: &str) -> Vec<MonitorSource> {
+    // Columns: index, name, driver, sample spec, state
+    output
+        .lines()
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split('\t').collect();
+            let name = columns.get(1)?;
+            if !name.ends_with(".monitor") {
+                return None;
+            }
+            Some(MonitorSource {
+                name: name.to_string(),
+                format: parse_sample_spec(columns.get(3)?)?,
+            })
+        })
+        .collect()
+}
+
+/// Name of the default sink's monitor, from the output of `pactl info`.
+fn parse_default_monitor(info: &str) -> Option<String> {
+    info.lines()
+        .find_map(|line| line.strip_prefix("Default Sink: "))
+        .map(|sink| format!("{}.monitor", sink.trim()))
+}
+
+/// Lists every monitor source known to the sound server.
+pub fn list_monitor_sources() -> Result<Vec<MonitorSource>, String> {
+    Ok(parse_monitor_sources(&pactl(&["list", "short", "sources"])?))
+}
+
+/// Picks the monitor of the default sink, so we record whatever the user is currently hearing.
+pub fn default_monitor_source() -> Option<MonitorSource> {
+    let sources = match list_monitor_sources() {
+        Ok(sources) => sources,
+        Err(e) => {
+            println!("⚠️ PulseAudio monitor sources unavailable: {}", e);
+            return None;
+        }
+    };
+
+    let default_monitor = pactl(&["info"]).ok().and_then(|info| parse_default_monitor(&info));
+    let source = match default_monitor {
+        Some(monitor) => sources.iter().find(|source| source.name == monitor).cloned(),
+        None => None,
+    }
+    .or_else(|| sources.into_iter().next());
+
+    if let Some(source) = &source {
+        println!("🔊 Found PulseAudio monitor source: {}", source.name);
+    }
+    source
+}
+
+/// A running `parec` process; recording stops when this is dropped.
+pub struct MonitorCapture {
+    child: Child,
+}
+
+impl Drop for MonitorCapture {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Records `source` through `parec` as interleaved f32 in `format`, calling `on_samples` for every block.
+/// `on_error` is called once if the stream ends without being dropped (e.g. the sink disappeared).
+pub fn start_monitor_capture<F, E>(source: &MonitorSource, format: CaptureFormat, mut on_samples: F, on_error: E) -> Result<MonitorCapture, String>
+where
+    F: FnMut(&[f32]) + Send + 'static,
+    E: FnOnce(String) + Send + 'static,
+{
+    let mut child = Command::new("parec")
+        .arg(format!("--device={}", source.name))
+        .arg("--raw")
+        .arg("--format=float32le")
+        .arg(format!("--rate={}", format.sample_rate))
+        .arg(format!("--channels={}", format.channels))
+        .arg("--latency-msec=50")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("parec not found: {}", MISSING_TOOLS),
+            _ => format!("Failed to start parec: {}", e),
+        })?;
+
+    let mut stdout = child.stdout.take().ok_or("Failed to read parec output")?;
+
+    std::thread::spawn(move || {
+        let mut bytes = vec![0u8; 16384];
+        let mut pending = Vec::new();
+        let mut samples = Vec::new();
+
+        loop {
+            let read = match stdout.read(&mut bytes) {
+                Ok(0) => {
+                    on_error("parec stopped delivering audio".to_string());
+                    return;
+                }
+                Ok(read) => read,
+                Err(e) => {
+                    on_error(format!("Failed to read parec output: {}", e));
+                    return;
+                }
+            };
+
+            // Reads are not aligned to sample boundaries
+            pending.extend_from_slice(&bytes[..read]);
+            let complete = pending.len() - pending.len() % 4;
+            samples.clear();
+            samples.extend(pending[..complete].chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])));
+            pending.drain(..complete);
+
+            on_samples(&samples);
+        }
+    });
+
+    Ok(MonitorCapture { child })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `pactl list short sources` on PipeWire with a USB headset and a Bluetooth speaker
+    const SHORT_SOURCES: &str = "\
+48\talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED
+49\talsa_input.pci-0000_00_1f.3.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED
+63\talsa_output.usb-Logitech_USB_Headset-00.mono-fallback.monitor\tPipeWire\ts16le 1ch 44100Hz\tIDLE
+64\talsa_input.usb-Logitech_USB_Headset-00.mono-fallback\tPipeWire\ts16le 1ch 44100Hz\tRUNNING
+71\tbluez_output.28_11_A5_01_02_03.1.monitor\tPipeWire\tfloat32le 2ch 48000Hz\tRUNNING
+";
+
+    // `pactl info` on the same machine
+    const INFO: &str = "\
+Server String: /run/user/1000/pulse/native
+Library Protocol Version: 35
+Server Protocol Version: 35
+Is Local: yes
+Client Index: 212
+Tile Size: 65472
+User Name: ana
+Host Name: laptop
+Server Name: PulseAudio (on PipeWire 1.0.5)
+Server Version: 15.0.0
+Default Sample Specification: float32le 2ch 48000Hz
+Default Channel Map: front-left,front-right
+Default Sink: bluez_output.28_11_A5_01_02_03.1
+Default Source: alsa_input.usb-Logitech_USB_Headset-00.mono-fallback
+Cookie: 1f2e:3d4c
+";
+
+    #[test]
+    fn parses_sample_specs() {
+        assert_eq!(parse_sample_spec("s16le 2ch 44100Hz"), Some(CaptureFormat { sample_rate: 44100, channels: 2 }));
+        assert_eq!(parse_sample_spec("float32le 1ch 48000Hz"), Some(CaptureFormat { sample_rate: 48000, channels: 1 }));
+        assert_eq!(parse_sample_spec("s16le 44100Hz"), None);
+        assert_eq!(parse_sample_spec(""), None);
+    }
+
+    #[test]
+    fn lists_only_monitor_sources() {
+        let sources = parse_monitor_sources(SHORT_SOURCES);
+        let names: Vec<&str> = sources.iter().map(|source| source.name.as_str()).collect();
+        assert_eq!(names, vec![
+            "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor",
+            "alsa_output.usb-Logitech_USB_Headset-00.mono-fallback.monitor",
+            "bluez_output.28_11_A5_01_02_03.1.monitor",
+        ]);
+        assert_eq!(sources[1].format, CaptureFormat { sample_rate: 44100, channels: 1 });
+    }
+
+    #[test]
+    fn skips_malformed_source_lines() {
+        let output = "1\tbroken.monitor\n2\tshort.monitor\tdriver\tno spec\tIDLE\n\n";
+        assert!(parse_monitor_sources(output).is_empty());
+    }
+
+    #[test]
+    fn finds_the_default_sink_monitor() {
+        assert_eq!(parse_default_monitor(INFO).as_deref(), Some("bluez_output.28_11_A5_01_02_03.1.monitor"));
+        assert_eq!(parse_default_monitor("Server Name: pulseaudio\n"), None);
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+/// Sentences shorter than this (in characters) are too short to compare meaningfully.
+const MIN_SENTENCE_CHARS: usize = 12;
+
+/// Sentences are compared by meaning, so paraphrases in the back-translation still score high.
+const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Back-translation check run after a translation to catch meaning that got lost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityCheckConfig {
+    /// Verify every translation unless a request says otherwise; each check costs an extra API call
+    pub enabled: bool,
+    /// Sentences whose back-translation is less similar than this (cosine similarity, 0–1) are flagged
+    pub divergence_threshold: f64,
+}
+
+impl Default for QualityCheckConfig {
+    fn default() -> Self {
+        QualityCheckConfig {
+            enabled: false,
+            divergence_threshold: 0.8,
+        }
+    }
+}
+
+impl QualityCheckConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.divergence_threshold) {
+            return Err("quality_check.divergence_threshold must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A source sentence and the part of the back-translation that corresponds to it best.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentenceDivergence {
+    pub source: String,
+    pub back_translation: String,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityReport {
+    /// Language the translation was translated back into
+    pub source_language: String,
+    pub back_translation: String,
+    /// Length-weighted sentence similarity (0–1) between the original and the back-translation
+    pub similarity: f64,
+    /// Sentences below the divergence threshold, in source order
+    pub divergent_sentences: Vec<SentenceDivergence>,
+}
+
+/// Splits text into sentences at terminal punctuation and line breaks.
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            sentences.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+        let terminal = matches!(c, '.' | '!' | '?' | '…') && chars.peek().is_none_or(|next| next.is_whitespace());
+        if terminal || matches!(c, '。' | '！' | '？') {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+    sentences.into_iter()
+        .map(|sentence| sentence.trim().to_string())
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| (*x as f64) * (*y as f64)).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms > 0.0 { dot / norms } else { 0.0 }
+}
+
+/// Embeds all texts with one request, in input order.
+async fn embed(api_key: &str, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post("https://api.openai.com/v1/embeddings")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({ "model": EMBEDDING_MODEL, "input": texts }))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("HTTP error: {} - {}", status, error_text));
+    }
+
+    let result: serde_json::Value = response.json().await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let embeddings: Vec<Vec<f32>> = result["data"].as_array()
+        .ok_or("No embeddings in response")?
+        .iter()
+        .map(|item| item["embedding"].as_array().map(|values| values.iter().filter_map(|value| value.as_f64()).map(|value| value as f32).collect()))
+        .collect::<Option<_>>()
+        .ok_or("Malformed embedding in response")?;
+    if embeddings.len() != texts.len() {
+        return Err(format!("Expected {} embeddings, got {}", texts.len(), embeddings.len()));
+    }
+    Ok(embeddings)
+}
+
+/// Compares the original with its back-translation sentence by sentence. Each source sentence is
+/// matched with the most similar back-translated sentence near the same relative position, since
+/// the translation may have merged or split sentences. `similarity(source, back)` takes indices.
+fn compare<F: Fn(usize, usize) -> f64>(
+    source_language: &str,
+    sources: &[String],
+    backs: &[String],
+    back_translation: &str,
+    threshold: f64,
+    similarity: F,
+) -> QualityReport {
+    let mut divergent_sentences = Vec::new();
+    let mut weighted = 0.0;
+    let mut total_weight = 0.0;
+    for (index, source) in sources.iter().enumerate() {
+        let expected = index * backs.len() / sources.len().max(1);
+        let (best, score) = (expected.saturating_sub(2)..(expected + 3).min(backs.len()))
+            .map(|back| (back, similarity(index, back)))
+            .fold((None, 0.0), |best, (back, score)| if score > best.1 { (Some(back), score) } else { best });
+
+        let weight = source.chars().count() as f64;
+        weighted += score * weight;
+        total_weight += weight;
+        if score < threshold && source.chars().count() >= MIN_SENTENCE_CHARS {
+            divergent_sentences.push(SentenceDivergence {
+                source: source.clone(),
+                back_translation: best.map(|back| backs[back].clone()).unwrap_or_default(),
+                similarity: score,
+            });
+        }
+    }
+
+    QualityReport {
+        source_language: source_language.to_string(),
+        back_translation: back_translation.to_string(),
+        similarity: if total_weight > 0.0 { weighted / total_weight } else { 1.0 },
+        divergent_sentences,
+    }
+}
+
+/// Translates `translation` back into `source_language` and compares the result with `original`.
+pub async fn check(
+    api_key: &str,
+    config: &QualityCheckConfig,
+    model: &str,
+    original: &str,
+    translation: &str,
+    source_language: &str,
+) -> Result<QualityReport, String> {
+    let payload = serde_json::json!({
+        "model": model,
+        "messages": [
+            {
+                "role": "system",
+                "content": format!(
+                    "Translate the following text into {}. Translate literally, sentence by sentence, and keep the sentence boundaries. Do not correct, complete or improve anything: the result is compared with the original to find meaning lost in translation. Only return the translation.",
+                    source_language
+                )
+            },
+            {
+                "role": "user",
+                "content": translation
+            }
+        ],
+        "temperature": 0.0,
+        "stream": true
+    });
+    let back_translation = crate::stream_chat_completion(api_key, payload, |_| {}).await
+        .map_err(|e| format!("Back-translation failed: {}", e))?;
+    let back_translation = back_translation.trim();
+
+    let sources = split_sentences(original);
+    let backs = split_sentences(back_translation);
+    let embeddings = embed(api_key, &[sources.clone(), backs.clone()].concat()).await
+        .map_err(|e| format!("Back-translation comparison failed: {}", e))?;
+    let (source_embeddings, back_embeddings) = embeddings.split_at(sources.len());
+    let report = compare(source_language, &sources, &backs, back_translation, config.divergence_threshold, |source, back| {
+        cosine(&source_embeddings[source], &back_embeddings[back])
+    });
+    println!(
+        "🔁 Back-translation similarity {:.0}%, {} divergent sentence(s)",
+        report.similarity * 100.0,
+        report.divergent_sentences.len()
+    );
+    Ok(report)
+}
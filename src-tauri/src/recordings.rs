@@ -0,0 +1,411 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::{credentials, diarization, settings, CaptureFormat, DiarizedTranscriptionResult, RecordedTracks, RecordingPause};
+
+const INDEX_FILE: &str = "index.json";
+
+lazy_static::lazy_static! {
+    // Serializes read-modify-write cycles on the index file
+    static ref LIBRARY_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// How recordings are written to disk.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    /// Lossless copy of the capture (32-bit float, original rate and channels)
+    WavFloat32,
+    /// 16-bit PCM at the original rate and channels, half the size of float
+    WavPcm16,
+    /// Mono 16 kHz 16-bit PCM, the smallest option that still transcribes well
+    WavSpeech16k,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingLibraryConfig {
+    pub enabled: bool,
+    pub format: StorageFormat,
+    /// Keep at most this many recordings, deleting the oldest first
+    pub max_recordings: Option<usize>,
+    /// Delete recordings older than this many days
+    pub max_age_days: Option<u32>,
+}
+
+impl Default for RecordingLibraryConfig {
+    fn default() -> Self {
+        RecordingLibraryConfig {
+            enabled: true,
+            format: StorageFormat::WavPcm16,
+            max_recordings: Some(50),
+            max_age_days: Some(30),
+        }
+    }
+}
+
+/// Where the capture came from, recorded when the session starts.
+#[derive(Debug, Clone, Default)]
+pub struct SessionInfo {
+    pub started_at: Option<chrono::DateTime<chrono::Local>>,
+    pub device_name: Option<String>,
+    pub microphone_device_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingEntry {
+    pub id: String,
+    pub started_at: String,
+    pub stopped_at: String,
+    pub duration_seconds: f32,
+    pub device_name: Option<String>,
+    pub microphone_device_name: Option<String>,
+    pub storage_format: StorageFormat,
+    pub system_file: Option<String>,
+    pub system_format: Option<CaptureFormat>,
+    pub microphone_file: Option<String>,
+    pub microphone_format: Option<CaptureFormat>,
+    pub pauses: Vec<RecordingPause>,
+    pub size_bytes: u64,
+    pub transcription: Option<String>,
+    pub transcription_error: Option<String>,
+}
+
+pub fn library_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join("recordings");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+    Ok(dir)
+}
+
+fn load_index(dir: &Path) -> Result<Vec<RecordingEntry>, String> {
+    match std::fs::read_to_string(dir.join(INDEX_FILE)) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse recordings index: {}", e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read recordings index: {}", e)),
+    }
+}
+
+fn save_index(dir: &Path, entries: &[RecordingEntry]) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize recordings index: {}", e))?;
+
+    // Write to a temporary file first so a crash never leaves a truncated index
+    let temp_path = dir.join(format!("{}.tmp", INDEX_FILE));
+    std::fs::write(&temp_path, contents)
+        .map_err(|e| format!("Failed to write recordings index: {}", e))?;
+    std::fs::rename(&temp_path, dir.join(INDEX_FILE))
+        .map_err(|e| format!("Failed to replace recordings index: {}", e))
+}
+
+fn update_entry<F: FnOnce(&mut RecordingEntry)>(dir: &Path, id: &str, update: F) -> Result<RecordingEntry, String> {
+    let _guard = LIBRARY_LOCK.lock().map_err(|_| "Failed to acquire recordings lock")?;
+    let mut entries = load_index(dir)?;
+    let entry = entries.iter_mut()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Recording {} not found", id))?;
+    update(entry);
+    let updated = entry.clone();
+    save_index(dir, &entries)?;
+    Ok(updated)
+}
+
+/// Writes one track in the configured storage format and returns the format it ended up in.
+fn write_track(path: &Path, samples: &[f32], format: CaptureFormat, storage: StorageFormat) -> Result<CaptureFormat, String> {
+    let (samples, stored_format) = match storage {
+        StorageFormat::WavSpeech16k => {
+            let (mono, sample_rate) = diarization::prepare_for_analysis(samples, format.sample_rate, format.channels);
+            (mono, CaptureFormat { sample_rate, channels: 1 })
+        }
+        _ => (samples.to_vec(), format),
+    };
+
+    let spec = match storage {
+        StorageFormat::WavFloat32 => hound::WavSpec {
+            channels: stored_format.channels,
+            sample_rate: stored_format.sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        },
+        StorageFormat::WavPcm16 | StorageFormat::WavSpeech16k => hound::WavSpec {
+            channels: stored_format.channels,
+            sample_rate: stored_format.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        },
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    for &sample in &samples {
+        let result = match spec.sample_format {
+            hound::SampleFormat::Float => writer.write_sample(sample),
+            hound::SampleFormat::Int => writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
+        };
+        result.map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    writer.finalize()
+        .map_err(|e| format!("Failed to finalize {}: {}", path.display(), e))?;
+
+    Ok(stored_format)
+}
+
+fn read_track(path: &Path) -> Result<(Vec<f32>, CaptureFormat), String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let spec = reader.spec();
+
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|sample| sample.map(|value| value as f32 / scale))
+                .collect::<Result<Vec<_>, _>>()
+        }
+    }
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    Ok((samples, CaptureFormat { sample_rate: spec.sample_rate, channels: spec.channels }))
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0)
+}
+
+fn remove_files(dir: &Path, entry: &RecordingEntry) {
+    for file in entry.system_file.iter().chain(entry.microphone_file.iter()) {
+        if let Err(e) = std::fs::remove_file(dir.join(file)) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                println!("⚠️ Failed to delete {}: {}", file, e);
+            }
+        }
+    }
+}
+
+/// Drops the oldest recordings that fall outside the retention policy.
+/// Expects `entries` to be sorted newest first.
+fn apply_retention(dir: &Path, entries: &mut Vec<RecordingEntry>, config: &RecordingLibraryConfig) {
+    let now = chrono::Local::now();
+    let mut kept = Vec::with_capacity(entries.len());
+
+    for (position, entry) in entries.drain(..).enumerate() {
+        let too_many = config.max_recordings.is_some_and(|max| position >= max);
+        let too_old = config.max_age_days.is_some_and(|days| {
+            chrono::DateTime::parse_from_rfc3339(&entry.started_at)
+                .map(|started| now.signed_duration_since(started) > chrono::Duration::days(days as i64))
+                .unwrap_or(false)
+        });
+
+        if too_many || too_old {
+            println!("🗑️ Retention policy removed recording {}", entry.id);
+            remove_files(dir, &entry);
+        } else {
+            kept.push(entry);
+        }
+    }
+
+    *entries = kept;
+}
+
+/// Persists a finished session and indexes it. Returns `None` when the library is disabled.
+pub fn save_recording(app_handle: &tauri::AppHandle, tracks: &RecordedTracks, session: &SessionInfo) -> Result<Option<RecordingEntry>, String> {
+    let dir = library_dir(app_handle)?;
+    let config = settings::current(app_handle).recordings;
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let stopped_at = chrono::Local::now();
+    let started_at = session.started_at.unwrap_or(stopped_at);
+    let id = format!("rec-{}", started_at.format("%Y%m%d-%H%M%S"));
+
+    let mut size_bytes = 0;
+    let (system_file, system_format) = if tracks.audio_data.is_empty() {
+        (None, None)
+    } else {
+        let file = format!("{}-system.wav", id);
+        let stored = write_track(&dir.join(&file), &tracks.audio_data, tracks.format, config.format)?;
+        size_bytes += file_size(&dir.join(&file));
+        (Some(file), Some(stored))
+    };
+
+    let (microphone_file, microphone_format) = match (&tracks.microphone_data, tracks.microphone_format) {
+        (Some(data), Some(format)) if !data.is_empty() => {
+            let file = format!("{}-microphone.wav", id);
+            let stored = write_track(&dir.join(&file), data, format, config.format)?;
+            size_bytes += file_size(&dir.join(&file));
+            (Some(file), Some(stored))
+        }
+        _ => (None, None),
+    };
+
+    let duration_seconds = tracks.audio_data.len() as f32
+        / (tracks.format.sample_rate as f32 * tracks.format.channels.max(1) as f32);
+
+    let entry = RecordingEntry {
+        id,
+        started_at: started_at.to_rfc3339(),
+        stopped_at: stopped_at.to_rfc3339(),
+        duration_seconds,
+        device_name: session.device_name.clone(),
+        microphone_device_name: session.microphone_device_name.clone(),
+        storage_format: config.format,
+        system_file,
+        system_format,
+        microphone_file,
+        microphone_format,
+        pauses: tracks.pauses.clone(),
+        size_bytes,
+        transcription: None,
+        transcription_error: None,
+    };
+
+    let _guard = LIBRARY_LOCK.lock().map_err(|_| "Failed to acquire recordings lock")?;
+    let mut entries = load_index(&dir)?;
+    entries.retain(|existing| existing.id != entry.id);
+    entries.insert(0, entry.clone());
+    apply_retention(&dir, &mut entries, &config);
+    save_index(&dir, &entries)?;
+
+    println!("💾 Saved recording {} ({:.1}s, {} bytes)", entry.id, entry.duration_seconds, entry.size_bytes);
+    Ok(Some(entry))
+}
+
+/// Stores the outcome of a transcription on the recording's index entry.
+pub fn record_transcription(app_handle: &tauri::AppHandle, id: &str, transcription: Option<&str>, error: Option<&str>) -> Result<(), String> {
+    let dir = library_dir(app_handle)?;
+    update_entry(&dir, id, |entry| {
+        entry.transcription = transcription.map(str::to_string);
+        entry.transcription_error = error.map(str::to_string);
+    })?;
+    Ok(())
+}
+
+fn load_tracks(dir: &Path, entry: &RecordingEntry) -> Result<RecordedTracks, String> {
+    let (audio_data, format) = match &entry.system_file {
+        Some(file) => read_track(&dir.join(file))?,
+        None => (Vec::new(), entry.system_format.unwrap_or(CaptureFormat { sample_rate: 16000, channels: 1 })),
+    };
+
+    let (microphone_data, microphone_format) = match &entry.microphone_file {
+        Some(file) => {
+            let (data, format) = read_track(&dir.join(file))?;
+            (Some(data), Some(format))
+        }
+        None => (None, None),
+    };
+
+    Ok(RecordedTracks {
+        audio_data,
+        format,
+        microphone_data,
+        microphone_format,
+        pauses: entry.pauses.clone(),
+    })
+}
+
+fn find_entry(dir: &Path, id: &str) -> Result<RecordingEntry, String> {
+    load_index(dir)?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Recording {} not found", id))
+}
+
+#[tauri::command]
+pub async fn list_recordings(app_handle: tauri::AppHandle) -> Result<Vec<RecordingEntry>, String> {
+    let dir = library_dir(&app_handle)?;
+    load_index(&dir)
+}
+
+#[tauri::command]
+pub async fn retranscribe_recording(app_handle: tauri::AppHandle, id: String, max_speakers: Option<usize>) -> Result<DiarizedTranscriptionResult, String> {
+    let api_key = credentials::api_key(&app_handle)?;
+
+    let dir = library_dir(&app_handle)?;
+    let entry = find_entry(&dir, &id)?;
+    let tracks = tokio::task::spawn_blocking({
+        let dir = dir.clone();
+        move || load_tracks(&dir, &entry)
+    })
+    .await
+    .map_err(|e| format!("Failed to load recording: {}", e))??;
+
+    println!("🔁 Re-transcribing recording {}...", id);
+    let processing = settings::current(&app_handle).audio_processing;
+    let result = match crate::transcribe_tracks(&api_key, &tracks, max_speakers, &processing).await {
+        Ok(segments) => DiarizedTranscriptionResult {
+            success: true,
+            transcription: Some(diarization::format_speaker_transcript(&segments, &tracks.pauses)),
+            segments: Some(segments),
+            error: None,
+        },
+        Err(e) => DiarizedTranscriptionResult {
+            success: false,
+            segments: None,
+            transcription: None,
+            error: Some(e),
+        },
+    };
+
+    record_transcription(&app_handle, &id, result.transcription.as_deref(), result.error.as_deref())?;
+    Ok(result)
+}
+
+/// Copies a recording's audio files (and its transcript, if any) into `destination`.
+#[tauri::command]
+pub async fn export_recording(app_handle: tauri::AppHandle, id: String, destination: String) -> Result<Vec<String>, String> {
+    let dir = library_dir(&app_handle)?;
+    let entry = find_entry(&dir, &id)?;
+    let destination = PathBuf::from(destination);
+    std::fs::create_dir_all(&destination)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let mut exported = Vec::new();
+    for file in entry.system_file.iter().chain(entry.microphone_file.iter()) {
+        let target = destination.join(file);
+        std::fs::copy(dir.join(file), &target)
+            .map_err(|e| format!("Failed to export {}: {}", file, e))?;
+        exported.push(target.to_string_lossy().to_string());
+    }
+
+    if let Some(transcription) = &entry.transcription {
+        let target = destination.join(format!("{}.txt", entry.id));
+        std::fs::write(&target, transcription)
+            .map_err(|e| format!("Failed to export transcript: {}", e))?;
+        exported.push(target.to_string_lossy().to_string());
+    }
+
+    println!("📤 Exported recording {} to {}", id, destination.display());
+    Ok(exported)
+}
+
+#[tauri::command]
+pub async fn delete_recording(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    let dir = library_dir(&app_handle)?;
+    let _guard = LIBRARY_LOCK.lock().map_err(|_| "Failed to acquire recordings lock")?;
+    let mut entries = load_index(&dir)?;
+    let position = entries.iter()
+        .position(|entry| entry.id == id)
+        .ok_or_else(|| format!("Recording {} not found", id))?;
+
+    let entry = entries.remove(position);
+    remove_files(&dir, &entry);
+    save_index(&dir, &entries)?;
+
+    println!("🗑️ Deleted recording {}", id);
+    Ok(())
+}
+
+/// Enforces `config` on the existing library, e.g. after the limits were lowered.
+pub fn apply_retention_policy(app_handle: &tauri::AppHandle, config: &RecordingLibraryConfig) -> Result<(), String> {
+    let dir = library_dir(app_handle)?;
+    let _guard = LIBRARY_LOCK.lock().map_err(|_| "Failed to acquire recordings lock")?;
+    let mut entries = load_index(&dir)?;
+    apply_retention(&dir, &mut entries, config);
+    save_index(&dir, &entries)
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::{credentials, settings, ResponseMetadata};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewriteMode {
+    /// Spelling, grammar and punctuation only
+    FixGrammar,
+    Shorten,
+    Formal,
+    Friendly,
+}
+
+impl RewriteMode {
+    fn instruction(self) -> &'static str {
+        match self {
+            RewriteMode::FixGrammar => "Fix spelling, grammar and punctuation only. Keep the wording, tone and structure otherwise unchanged; if the text is already correct, return it unchanged.",
+            RewriteMode::Shorten => "Make the text noticeably shorter and more direct while keeping every piece of information that matters.",
+            RewriteMode::Formal => "Make the text more formal and professional, suitable for customers or management.",
+            RewriteMode::Friendly => "Make the text warmer and friendlier while keeping it professional.",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RewriteRequest {
+    pub text: String,
+    pub mode: RewriteMode,
+    pub credential: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of text that is unchanged, added or removed by the rewrite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffSpan {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewriteResult {
+    pub rewritten: String,
+    pub mode: RewriteMode,
+    /// Word-level changes from the original to the rewritten text
+    pub diff: Vec<DiffSpan>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewriteResponse {
+    #[serde(flatten)]
+    pub result: RewriteResult,
+    pub metadata: ResponseMetadata,
+}
+
+/// Word-level diff; consecutive changes of the same kind are merged into one span.
+pub fn diff(original: &str, rewritten: &str) -> Vec<DiffSpan> {
+    let mut spans: Vec<DiffSpan> = Vec::new();
+    for change in similar::TextDiff::from_words(original, rewritten).iter_all_changes() {
+        let op = match change.tag() {
+            similar::ChangeTag::Equal => DiffOp::Equal,
+            similar::ChangeTag::Insert => DiffOp::Insert,
+            similar::ChangeTag::Delete => DiffOp::Delete,
+        };
+        match spans.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(change.value()),
+            _ => spans.push(DiffSpan { op, text: change.value().to_string() }),
+        }
+    }
+    spans
+}
+
+fn payload(settings: &settings::Settings, text: &str, mode: RewriteMode) -> serde_json::Value {
+    serde_json::json!({
+        "model": settings.model,
+        "messages": [
+            {
+                "role": "system",
+                "content": format!(
+                    "You are an editor. Rewrite the text the user sends. {} Keep its language, its meaning and its formatting (line breaks, lists, Markdown, @mentions, emoji, links and code). Only return the rewritten text, without quotes or comments.",
+                    mode.instruction()
+                )
+            },
+            {
+                "role": "user",
+                "content": text
+            }
+        ],
+        "temperature": settings.temperature,
+        // A rewrite is about as long as the original; allow for growth when it gets friendlier or more formal
+        "max_tokens": (text.chars().count() / 2 + 200).min(4000),
+        "stream": true
+    })
+}
+
+async fn rewrite<F: FnMut(&str)>(api_key: &str, settings: &settings::Settings, text: &str, mode: RewriteMode, on_chunk: F) -> Result<RewriteResult, String> {
+    println!("✍️ Rewriting text ({} chars, {:?})...", text.len(), mode);
+    let rewritten = crate::stream_chat_completion(api_key, payload(settings, text, mode), on_chunk).await?;
+    let rewritten = rewritten.trim().to_string();
+    if rewritten.is_empty() {
+        return Err("No rewritten text in response".to_string());
+    }
+    let diff = diff(text, &rewritten);
+    Ok(RewriteResult { rewritten, mode, diff })
+}
+
+#[tauri::command]
+pub async fn rewrite_text(app_handle: tauri::AppHandle, request: RewriteRequest) -> Result<RewriteResponse, String> {
+    if request.text.trim().is_empty() {
+        return Err("No text provided to rewrite".to_string());
+    }
+    let credential = credentials::resolve(&app_handle, request.credential.as_deref())?;
+    let settings = settings::current(&app_handle);
+
+    let result = rewrite(&credential.api_key, &settings, &request.text, request.mode, |_| {}).await?;
+    Ok(RewriteResponse {
+        result,
+        metadata: ResponseMetadata {
+            credential: credential.name,
+            model: settings.model,
+        },
+    })
+}
+
+/// Opens the rewrite window and streams the rewrite of `text` into it; the window can re-run other modes.
+#[tauri::command]
+pub async fn show_rewrite_window(app_handle: tauri::AppHandle, text: String, mode: Option<RewriteMode>) -> Result<(), String> {
+    let mode = mode.unwrap_or(RewriteMode::FixGrammar);
+    // Fail fast before opening a window without a usable key
+    let credential = credentials::resolve(&app_handle, None)?;
+    let settings = settings::current(&app_handle);
+
+    let window = tauri::WebviewWindowBuilder::new(
+        &app_handle,
+        "rewriter",
+        tauri::WebviewUrl::App("rewriter.html".into())
+    )
+    .title("Rewrite")
+    .inner_size(700.0, 550.0)
+    .center()
+    .resizable(true)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    // Wait for window to load before sending events
+    tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+
+    let _ = window.emit("clipboard-text", &text);
+    let _ = window.emit("rewrite-mode", mode);
+    let _ = window.emit("rewrite-metadata", &ResponseMetadata {
+        credential: credential.name.clone(),
+        model: settings.model.clone(),
+    });
+
+    let result = rewrite(&credential.api_key, &settings, &text, mode, |chunk| {
+        let _ = window.emit("rewrite-chunk", chunk);
+    })
+    .await;
+    match result {
+        Ok(result) => {
+            println!("🎉 REWRITE SUCCESS: {} diff spans", result.diff.len());
+            let _ = window.emit("rewrite-complete", &result);
+        }
+        Err(e) => {
+            println!("❌ Rewrite failed: {}", e);
+            let _ = window.emit("rewrite-error", &e);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,484 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::alternatives::AlternativesConfig;
+use crate::credentials;
+use crate::dsp::AudioProcessingConfig;
+use crate::language::SmartTargetRule;
+use crate::pipeline::{self, Pipeline};
+use crate::prompts::PromptTemplates;
+use crate::quality::QualityCheckConfig;
+use crate::recordings::{self, RecordingLibraryConfig};
+use crate::style::TranslationStyle;
+use crate::summary::SummaryOptions;
+use crate::translation_memory::TranslationMemoryConfig;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Bump this and add a step to `upgrade` whenever a field is renamed or changes meaning.
+pub const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// Language the translator targets unless a request names another one
+    pub target_language: String,
+    /// Switches to another language when the text is already in `target_language`
+    pub smart_target: SmartTargetRule,
+    /// Further languages the translation window translates into, each in its own tab
+    pub extra_target_languages: Vec<String>,
+    pub translation_memory: TranslationMemoryConfig,
+    /// Formality, tone and locale last used per target language (keyed by lowercase language name)
+    pub translation_styles: BTreeMap<String, TranslationStyle>,
+    pub quality_check: QualityCheckConfig,
+    pub alternatives: AlternativesConfig,
+    /// Chat completion model used by every LLM feature
+    pub model: String,
+    pub temperature: f32,
+    /// Named API key (e.g. personal, team, project) used when a request doesn't pick one
+    pub active_credential: String,
+    /// Record the default microphone as a second track
+    pub record_microphone: bool,
+    pub silence_warning_seconds: u64,
+    /// Reopen capture on another device when the current one disappears
+    pub device_failover: bool,
+    pub recordings: RecordingLibraryConfig,
+    pub audio_processing: AudioProcessingConfig,
+    /// System/user templates for translation and summarization
+    pub prompts: PromptTemplates,
+    /// Summary style used when a request doesn't specify one
+    pub summary: SummaryOptions,
+    /// Saved chains of transcribe, translate, summarize and prompt steps, keyed by id
+    pub pipelines: BTreeMap<String, Pipeline>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            target_language: "German".to_string(),
+            smart_target: SmartTargetRule::default(),
+            extra_target_languages: Vec::new(),
+            translation_memory: TranslationMemoryConfig::default(),
+            translation_styles: BTreeMap::new(),
+            quality_check: QualityCheckConfig::default(),
+            alternatives: AlternativesConfig::default(),
+            model: "gpt-4o-mini".to_string(),
+            temperature: 0.3,
+            active_credential: credentials::DEFAULT_CREDENTIAL.to_string(),
+            record_microphone: false,
+            silence_warning_seconds: 10,
+            device_failover: true,
+            recordings: RecordingLibraryConfig::default(),
+            audio_processing: AudioProcessingConfig::default(),
+            prompts: PromptTemplates::default(),
+            summary: SummaryOptions::default(),
+            pipelines: pipeline::defaults(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target_language.trim().is_empty() {
+            return Err("target_language must not be empty".to_string());
+        }
+        self.smart_target.validate()?;
+        if self.extra_target_languages.iter().any(|language| language.trim().is_empty()) {
+            return Err("extra_target_languages must not contain empty names".to_string());
+        }
+        self.translation_memory.validate()?;
+        for style in self.translation_styles.values() {
+            style.validate()?;
+        }
+        self.quality_check.validate()?;
+        self.alternatives.validate()?;
+        if self.model.trim().is_empty() {
+            return Err("model must not be empty".to_string());
+        }
+        if !(0.0..=2.0).contains(&self.temperature) {
+            return Err("temperature must be between 0 and 2".to_string());
+        }
+        credentials::validate_name(&self.active_credential)?;
+        if self.silence_warning_seconds == 0 {
+            return Err("silence_warning_seconds must be at least 1".to_string());
+        }
+        if self.recordings.max_recordings == Some(0) {
+            return Err("recordings.max_recordings must be at least 1".to_string());
+        }
+        self.audio_processing.validate()?;
+        self.prompts.validate()?;
+        self.summary.validate()?;
+        for (id, pipeline) in &self.pipelines {
+            pipeline.validate().map_err(|e| format!("pipelines.{}: {}", id, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// The settings file plus its in-memory copy, kept in Tauri state.
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Reads (and if needed migrates) the settings file from the app config directory.
+    pub fn load(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let config_dir = app_handle.path().app_config_dir()
+            .map_err(|e| format!("Failed to resolve app config directory: {}", e))?;
+        let data_dir = app_handle.path().app_data_dir()
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+        std::fs::create_dir_all(&config_dir)
+            .map_err(|e| format!("Failed to create app config directory: {}", e))?;
+
+        let path = config_dir.join(SETTINGS_FILE);
+        let stored = match std::fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(value) => value,
+                Err(e) => {
+                    // Keep the broken file around for inspection and start over
+                    println!("⚠️ Settings file is corrupt, using defaults: {}", e);
+                    let _ = std::fs::rename(&path, path.with_extension("json.bak"));
+                    serde_json::json!({ "version": SETTINGS_VERSION })
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => serde_json::json!({}),
+            Err(e) => return Err(format!("Failed to read settings: {}", e)),
+        };
+
+        let stored_version = stored.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let (upgraded, legacy_files) = upgrade(stored, &data_dir)?;
+        let (settings, reset) = match parse(upgraded) {
+            Ok(settings) => (settings, false),
+            Err(e) => {
+                // Like a corrupt file: one bad hand-edited value must not keep the app from starting
+                println!("⚠️ Invalid settings in {}, using defaults: {}", path.display(), e);
+                let _ = std::fs::rename(&path, path.with_extension("json.bak"));
+                (Settings::default(), true)
+            }
+        };
+
+        let store = SettingsStore {
+            path,
+            settings: Mutex::new(settings.clone()),
+        };
+        if reset {
+            store.save(&settings)?;
+        } else if stored_version != SETTINGS_VERSION {
+            println!("⚙️ Migrated settings from version {} to {}", stored_version, SETTINGS_VERSION);
+            store.save(&settings)?;
+            // Only now that their contents are persisted in the settings file
+            for legacy_file in legacy_files {
+                let _ = std::fs::remove_file(legacy_file);
+            }
+        }
+        Ok(store)
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, settings: &Settings) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+        // Write to a temporary file first so a crash never leaves truncated settings
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, contents)
+            .map_err(|e| format!("Failed to write settings: {}", e))?;
+        std::fs::rename(&temp_path, &self.path)
+            .map_err(|e| format!("Failed to replace settings: {}", e))
+    }
+
+    /// Applies `change` to the current settings, validates and persists the result. The lock is held
+    /// throughout so concurrent updates can't overwrite each other. Returns the previous and new settings.
+    fn update<F: FnOnce(&Settings) -> Result<Settings, String>>(&self, change: F) -> Result<(Settings, Settings), String> {
+        let mut stored = self.settings.lock().map_err(|_| "Failed to acquire settings lock")?;
+        let previous = stored.clone();
+        let mut settings = change(&previous)?;
+        settings.version = SETTINGS_VERSION;
+        settings.validate()?;
+
+        if settings != previous {
+            self.save(&settings)?;
+            *stored = settings.clone();
+        }
+        Ok((previous, settings))
+    }
+}
+
+/// Brings a stored settings document up to `SETTINGS_VERSION`, one version at a time. Also returns
+/// the legacy files that were imported, to be deleted once the upgraded settings are saved.
+fn upgrade(mut value: serde_json::Value, data_dir: &Path) -> Result<(serde_json::Value, Vec<PathBuf>), String> {
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    if version > SETTINGS_VERSION {
+        return Err(format!("Settings were written by a newer version of the app (version {})", version));
+    }
+
+    let mut imported = Vec::new();
+    if version < 1 {
+        // Before the settings file existed, the recordings library and audio processing had their own files
+        let legacy_files = [
+            ("recordings", data_dir.join("recordings").join("config.json")),
+            ("audio_processing", data_dir.join("audio-processing.json")),
+        ];
+        for (key, path) in legacy_files {
+            let legacy = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok());
+            if let (Some(legacy), Some(object)) = (legacy, value.as_object_mut()) {
+                println!("⚙️ Importing {} into settings", path.display());
+                object.insert(key.to_string(), legacy);
+                imported.push(path);
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), SETTINGS_VERSION.into());
+    }
+    Ok((value, imported))
+}
+
+/// Deserializes and validates an upgraded settings document.
+fn parse(value: serde_json::Value) -> Result<Settings, String> {
+    let settings: Settings = serde_json::from_value(value)
+        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// JSON merge patch (RFC 7386): overlays `patch` onto `target` so callers can send only the fields
+/// they change. A `null` removes the key, which deletes map entries such as a saved pipeline and
+/// resets other fields to their defaults.
+fn merge(target: &mut serde_json::Value, patch: serde_json::Value) {
+    let serde_json::Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::Value::Object(serde_json::Map::new());
+    }
+    if let serde_json::Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge(target.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Snapshot of the current settings.
+pub fn current(app_handle: &tauri::AppHandle) -> Settings {
+    app_handle.state::<SettingsStore>().get()
+}
+
+#[tauri::command]
+pub async fn get_settings(store: tauri::State<'_, SettingsStore>) -> Result<Settings, String> {
+    Ok(store.get())
+}
+
+/// Applies a partial update, persists it and broadcasts `settings-changed` to every window.
+#[tauri::command]
+pub async fn update_settings(
+    app_handle: tauri::AppHandle,
+    store: tauri::State<'_, SettingsStore>,
+    patch: serde_json::Value,
+) -> Result<Settings, String> {
+    commit(&app_handle, &store, |previous| apply_patch(previous, patch))
+}
+
+fn apply_patch(previous: &Settings, patch: serde_json::Value) -> Result<Settings, String> {
+    let mut value = serde_json::to_value(previous)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    merge(&mut value, patch);
+    serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))
+}
+
+/// Changes settings from the backend (e.g. to remember a choice made in a request).
+pub fn modify<F: FnOnce(&mut Settings)>(app_handle: &tauri::AppHandle, change: F) -> Result<Settings, String> {
+    let store = app_handle.state::<SettingsStore>();
+    commit(app_handle, &store, |previous| {
+        let mut settings = previous.clone();
+        change(&mut settings);
+        Ok(settings)
+    })
+}
+
+/// Applies and persists a change, then the side effects of what changed.
+fn commit<F: FnOnce(&Settings) -> Result<Settings, String>>(app_handle: &tauri::AppHandle, store: &SettingsStore, change: F) -> Result<Settings, String> {
+    let (previous, settings) = store.update(change)?;
+    if settings == previous {
+        return Ok(settings);
+    }
+    println!("⚙️ Settings updated");
+
+    // Apply a tightened retention policy right away instead of waiting for the next recording
+    if settings.recordings != previous.recordings {
+        recordings::apply_retention_policy(app_handle, &settings.recordings)?;
+    }
+
+    let _ = app_handle.emit("settings-changed", &settings);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// An empty directory under the system temp dir, unique to the test.
+    fn data_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("shorty-settings-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("recordings")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Settings::default().validate().is_ok());
+    }
+
+    #[test]
+    fn upgrade_imports_version_0_files_without_deleting_them() {
+        let dir = data_dir("upgrade");
+        let recordings = dir.join("recordings").join("config.json");
+        let audio_processing = dir.join("audio-processing.json");
+        std::fs::write(&recordings, r#"{ "enabled": false, "format": "wav_pcm16", "max_recordings": 20, "max_age_days": null }"#).unwrap();
+        std::fs::write(&audio_processing, r#"{ "high_pass": false, "high_pass_hz": 100.0, "noise_gate": true, "normalize": false, "target_lufs": -20.0 }"#).unwrap();
+
+        let (value, imported) = upgrade(json!({ "target_language": "French" }), &dir).unwrap();
+        assert_eq!(imported, vec![recordings.clone(), audio_processing.clone()]);
+        // Deleting them is up to the caller, once the upgraded settings are saved
+        assert!(recordings.exists() && audio_processing.exists());
+
+        let settings = parse(value).unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.target_language, "French");
+        assert!(!settings.recordings.enabled);
+        assert_eq!(settings.recordings.max_recordings, Some(20));
+        assert!(!settings.audio_processing.high_pass);
+        assert_eq!(settings.audio_processing.target_lufs, -20.0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn upgrade_of_current_settings_imports_nothing() {
+        let dir = data_dir("current");
+        std::fs::write(dir.join("audio-processing.json"), r#"{ "high_pass": false }"#).unwrap();
+
+        let (value, imported) = upgrade(json!({ "version": SETTINGS_VERSION, "model": "gpt-4o" }), &dir).unwrap();
+        assert!(imported.is_empty());
+        assert_eq!(parse(value).unwrap().audio_processing, AudioProcessingConfig::default());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn upgrade_rejects_newer_versions() {
+        let dir = data_dir("newer");
+        let error = upgrade(json!({ "version": SETTINGS_VERSION + 1 }), &dir).unwrap_err();
+        assert!(error.contains("newer version"), "{}", error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_rejects_wrong_types_and_invalid_values() {
+        assert!(parse(json!({ "model": 5 })).unwrap_err().starts_with("Failed to parse settings"));
+        assert_eq!(parse(json!({ "temperature": 3.0 })).unwrap_err(), "temperature must be between 0 and 2");
+        assert_eq!(parse(json!({ "model": " " })).unwrap_err(), "model must not be empty");
+        assert_eq!(parse(json!({})).unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn validate_checks_nested_sections() {
+        let settings = Settings { silence_warning_seconds: 0, ..Settings::default() };
+        assert_eq!(settings.validate().unwrap_err(), "silence_warning_seconds must be at least 1");
+
+        let mut settings = Settings::default();
+        settings.recordings.max_recordings = Some(0);
+        assert_eq!(settings.validate().unwrap_err(), "recordings.max_recordings must be at least 1");
+
+        let mut settings = Settings::default();
+        settings.prompts.translate.user = "Translate this.".to_string();
+        assert_eq!(settings.validate().unwrap_err(), "prompts.translate: template must contain {{text}}");
+
+        let mut settings = Settings::default();
+        settings.pipelines.values_mut().next().unwrap().steps.clear();
+        assert!(settings.validate().unwrap_err().starts_with("pipelines."));
+    }
+
+    #[test]
+    fn merge_overlays_nested_objects() {
+        let mut target = json!({ "a": 1, "nested": { "b": 2, "c": 3 }, "list": [1, 2] });
+        merge(&mut target, json!({ "nested": { "c": 4, "d": 5 }, "list": [3] }));
+        assert_eq!(target, json!({ "a": 1, "nested": { "b": 2, "c": 4, "d": 5 }, "list": [3] }));
+    }
+
+    #[test]
+    fn merge_removes_keys_set_to_null() {
+        let mut target = json!({ "a": 1, "nested": { "b": 2, "c": 3 } });
+        merge(&mut target, json!({ "a": null, "nested": { "b": null }, "missing": null }));
+        assert_eq!(target, json!({ "nested": { "c": 3 } }));
+    }
+
+    #[test]
+    fn merge_replaces_non_objects() {
+        let mut target = json!({ "a": "text" });
+        merge(&mut target, json!({ "a": { "b": 1, "c": null } }));
+        assert_eq!(target, json!({ "a": { "b": 1 } }));
+
+        let mut target = json!({ "a": 1 });
+        merge(&mut target, json!(["replaced"]));
+        assert_eq!(target, json!(["replaced"]));
+    }
+
+    #[test]
+    fn patches_change_only_the_given_fields() {
+        let settings = apply_patch(&Settings::default(), json!({ "model": "gpt-4o", "recordings": { "max_recordings": 5 } })).unwrap();
+        assert_eq!(settings.model, "gpt-4o");
+        assert_eq!(settings.recordings.max_recordings, Some(5));
+        assert_eq!(settings.recordings.enabled, Settings::default().recordings.enabled);
+        assert_eq!(settings.target_language, Settings::default().target_language);
+    }
+
+    #[test]
+    fn patches_delete_saved_pipelines() {
+        let mut previous = Settings::default();
+        previous.pipelines.insert("custom".to_string(), Pipeline {
+            name: "Custom".to_string(),
+            description: String::new(),
+            shortcut: Some("cmd+shift+p".to_string()),
+            steps: vec![pipeline::PipelineStep::Summarize { options: None }],
+        });
+
+        let settings = apply_patch(&previous, json!({ "pipelines": { "custom": null, "meeting-summary-en": null } })).unwrap();
+        assert_eq!(settings.pipelines.keys().collect::<Vec<_>>(), vec!["summarize-translate"]);
+
+        // Removing the seeded pipelines sticks: an empty map is saved, not replaced by the defaults
+        let settings = apply_patch(&settings, json!({ "pipelines": { "summarize-translate": null } })).unwrap();
+        assert!(settings.pipelines.is_empty());
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert!(parse(saved).unwrap().pipelines.is_empty());
+    }
+
+    #[test]
+    fn patches_delete_translation_styles() {
+        let mut previous = Settings::default();
+        previous.translation_styles.insert("german".to_string(), TranslationStyle { locale: Some("de-CH".to_string()), ..TranslationStyle::default() });
+        previous.translation_styles.insert("french".to_string(), TranslationStyle::default());
+
+        let settings = apply_patch(&previous, json!({ "translation_styles": { "german": null } })).unwrap();
+        assert_eq!(settings.translation_styles.keys().collect::<Vec<_>>(), vec!["french"]);
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Formality {
+    Formal,
+    Informal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tone {
+    Neutral,
+    Marketing,
+    Technical,
+    Casual,
+}
+
+impl Tone {
+    /// Value of the `{{tone}}` template variable.
+    pub fn name(self) -> &'static str {
+        match self {
+            Tone::Neutral => "neutral",
+            Tone::Marketing => "marketing",
+            Tone::Technical => "technical",
+            Tone::Casual => "casual",
+        }
+    }
+
+    fn instruction(self) -> Option<&'static str> {
+        match self {
+            Tone::Neutral => None,
+            Tone::Marketing => Some("Write in an engaging, persuasive marketing tone that reads as if originally written in the target language."),
+            Tone::Technical => Some("Write in a precise technical tone; keep terminology exact and consistent and do not paraphrase."),
+            Tone::Casual => Some("Write in a relaxed, conversational tone."),
+        }
+    }
+}
+
+/// Register, tone and regional variant of a translation. Unset fields fall back to what was
+/// last used for the same target language.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationStyle {
+    pub formality: Option<Formality>,
+    pub tone: Option<Tone>,
+    /// Regional variant as a BCP 47 tag, e.g. "pt-BR" or "de-CH"
+    pub locale: Option<String>,
+}
+
+impl TranslationStyle {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(locale) = &self.locale {
+            let valid = !locale.is_empty()
+                && locale.split('-').all(|part| (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric()));
+            if !valid {
+                return Err(format!("Invalid locale '{}', expected a tag like pt-BR", locale));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &TranslationStyle::default()
+    }
+
+    /// Fields set here win, the rest come from `fallback`.
+    pub fn or(&self, fallback: &TranslationStyle) -> TranslationStyle {
+        TranslationStyle {
+            formality: self.formality.or(fallback.formality),
+            tone: self.tone.or(fallback.tone),
+            locale: self.locale.clone().or_else(|| fallback.locale.clone()),
+        }
+    }
+
+    /// Short label distinguishing translations of the same text in different styles, e.g. "formal/technical/pt-BR".
+    pub fn fingerprint(&self) -> String {
+        let formality = match self.formality {
+            Some(Formality::Formal) => "formal",
+            Some(Formality::Informal) => "informal",
+            None => "",
+        };
+        let tone = self.tone.map(Tone::name).unwrap_or("");
+        format!("{}/{}/{}", formality, tone, self.locale.as_deref().unwrap_or(""))
+    }
+
+    /// Instructions appended to the translation system prompt.
+    pub fn prompt_section(&self, language: &str) -> Option<String> {
+        let mut lines = Vec::new();
+        if let Some(locale) = &self.locale {
+            lines.push(format!("Use the {} variant of {}: its spelling, vocabulary and conventions.", locale, language));
+        }
+        match self.formality {
+            Some(Formality::Formal) => lines.push("Use the formal register and polite forms of address (e.g. \"Sie\" in German, \"vous\" in French, desu/masu forms in Japanese).".to_string()),
+            Some(Formality::Informal) => lines.push("Use the informal register and familiar forms of address (e.g. \"du\" in German, \"tu\" in French, plain forms in Japanese).".to_string()),
+            None => {}
+        }
+        if let Some(instruction) = self.tone.and_then(Tone::instruction) {
+            lines.push(instruction.to_string());
+        }
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// Key under which a language's style is remembered in the settings.
+pub fn language_key(language: &str) -> String {
+    language.trim().to_lowercase()
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::prompts::{self, PromptKind, RenderedPrompt};
+use crate::settings::Settings;
+
+/// Longest summary a word limit may ask for.
+const MAX_WORD_LIMIT: u32 = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryMode {
+    /// A short paragraph of the key points (the original behaviour)
+    Standard,
+    /// A single sentence
+    Tldr,
+    Bullets,
+    /// Context, key points and decisions for someone who won't read the source
+    Executive,
+    ActionItems,
+}
+
+impl SummaryMode {
+    /// Style instruction appended to the summarize template.
+    fn instruction(self) -> Option<&'static str> {
+        match self {
+            SummaryMode::Standard => None,
+            SummaryMode::Tldr => Some("Respond with a single sentence (TL;DR) that captures the essence of the text."),
+            SummaryMode::Bullets => Some("Respond with a Markdown bullet list of the key points, one point per bullet, most important first."),
+            SummaryMode::Executive => Some("Write an executive summary: one sentence of context, then the key findings, then any decisions or recommendations. Keep it suitable for a reader who will not read the original."),
+            SummaryMode::ActionItems => Some("List only the action items as a Markdown checklist (\"- [ ] ...\"), naming the owner and due date when the text mentions them. If there are none, say so."),
+        }
+    }
+
+    fn max_tokens(self) -> u32 {
+        match self {
+            SummaryMode::Standard => 500,
+            SummaryMode::Tldr => 100,
+            SummaryMode::Bullets => 500,
+            SummaryMode::Executive => 700,
+            SummaryMode::ActionItems => 400,
+        }
+    }
+}
+
+/// How a summary should look; every field is optional in requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummaryOptions {
+    pub mode: SummaryMode,
+    /// Upper bound on the length of the summary, in words
+    pub word_limit: Option<u32>,
+    /// Language to write the summary in; the language of the text when unset
+    pub output_language: Option<String>,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        SummaryOptions {
+            mode: SummaryMode::Standard,
+            word_limit: None,
+            output_language: None,
+        }
+    }
+}
+
+impl SummaryOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(limit) = self.word_limit {
+            if limit == 0 || limit > MAX_WORD_LIMIT {
+                return Err(format!("word_limit must be between 1 and {}", MAX_WORD_LIMIT));
+            }
+        }
+        if self.output_language.as_deref().is_some_and(|language| language.trim().is_empty()) {
+            return Err("output_language must not be empty".to_string());
+        }
+        Ok(())
+    }
+
+    /// Token budget for the response: derived from the word limit when set, otherwise the mode's budget.
+    pub fn max_tokens(&self) -> u32 {
+        match self.word_limit {
+            // Roughly two tokens per word leaves room for non-English output and Markdown
+            Some(limit) => limit * 2 + 50,
+            None => self.mode.max_tokens(),
+        }
+    }
+}
+
+/// Renders the summarize template and appends the mode, length and language instructions.
+pub fn build_prompt(settings: &Settings, text: &str, options: &SummaryOptions) -> RenderedPrompt {
+    let mut prompt = prompts::render_for(settings, PromptKind::Summarize, HashMap::from([
+        ("target_language".to_string(), settings.target_language.clone()),
+        ("text".to_string(), text.to_string()),
+    ]));
+
+    if let Some(instruction) = options.mode.instruction() {
+        prompt.system.push_str("\n\n");
+        prompt.system.push_str(instruction);
+    }
+    if let Some(limit) = options.word_limit {
+        prompt.system.push_str(&format!("\n\nUse at most {} words.", limit));
+    }
+    if let Some(language) = &options.output_language {
+        prompt.system.push_str(&format!("\n\nWrite the summary in {}, regardless of the language of the text.", language.trim()));
+    }
+    prompt
+}
@@ -0,0 +1,563 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+const DATABASE_FILE: &str = "translation-memory.sqlite3";
+
+/// Stored in `PRAGMA user_version`; bump this and add a step to `migrate` whenever the table changes.
+const SCHEMA_VERSION: i64 = 1;
+
+const CREATE_ENTRIES: &str = "CREATE TABLE IF NOT EXISTS entries (
+    id INTEGER PRIMARY KEY,
+    source_key TEXT NOT NULL,
+    source_text TEXT NOT NULL,
+    translation TEXT NOT NULL,
+    target_language TEXT NOT NULL COLLATE NOCASE,
+    style TEXT NOT NULL DEFAULT '',
+    model TEXT NOT NULL,
+    glossary_version TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    last_used_at TEXT NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (source_key, target_language, style, model, glossary_version)
+);";
+
+/// Fuzzy candidates are only compared when their length is within this factor of the query.
+const FUZZY_LENGTH_RATIO: f64 = 0.8;
+
+/// How the local translation memory is used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationMemoryConfig {
+    /// Reuse earlier translations and store new ones
+    pub enabled: bool,
+    /// Minimum similarity (0–1) for an earlier translation to be offered to the model as a reference
+    pub fuzzy_threshold: f64,
+    /// Least recently used entries beyond this are dropped
+    pub max_entries: Option<u32>,
+}
+
+impl Default for TranslationMemoryConfig {
+    fn default() -> Self {
+        TranslationMemoryConfig {
+            enabled: true,
+            fuzzy_threshold: 0.85,
+            max_entries: Some(10_000),
+        }
+    }
+}
+
+impl TranslationMemoryConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.5..=1.0).contains(&self.fuzzy_threshold) {
+            return Err("translation_memory.fuzzy_threshold must be between 0.5 and 1".to_string());
+        }
+        if self.max_entries == Some(0) {
+            return Err("translation_memory.max_entries must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub id: i64,
+    pub source_text: String,
+    pub translation: String,
+    pub target_language: String,
+    pub style: String,
+    pub model: String,
+    pub glossary_version: String,
+    pub created_at: String,
+    pub last_used_at: String,
+    pub hits: u32,
+}
+
+/// An earlier translation of the same or a similar text; `score` is 1.0 for exact matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryMatch {
+    pub entry: MemoryEntry,
+    pub score: f64,
+}
+
+/// What a memory entry is keyed by besides the source text.
+#[derive(Debug, Clone)]
+pub struct MemoryKey {
+    pub target_language: String,
+    /// Formality, tone and locale fingerprint, see `TranslationStyle::fingerprint`
+    pub style: String,
+    pub model: String,
+    pub glossary_version: String,
+}
+
+/// Collapses whitespace so re-wrapped or re-indented copies of a text hit the same entry.
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn database_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(DATABASE_FILE))
+}
+
+/// Creates the table, or brings a database written by an older version up to `SCHEMA_VERSION`.
+fn migrate(connection: &mut Connection) -> Result<(), String> {
+    let to_error = |e: rusqlite::Error| format!("Failed to migrate translation memory: {}", e);
+    // Immediate, so a second connection waits instead of migrating the same database twice
+    let transaction = connection.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+        .map_err(to_error)?;
+    let version: i64 = transaction.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(to_error)?;
+    if version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let has_table: bool = transaction.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'entries')",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(to_error)?;
+    let has_style: bool = transaction.query_row(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info('entries') WHERE name = 'style')",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(to_error)?;
+
+    if has_table && !has_style {
+        // Version 0 had no style column; the unique key has to include it, and SQLite can
+        // only change constraints by rebuilding the table
+        println!("🧠 Migrating translation memory to schema version {}", SCHEMA_VERSION);
+        transaction.execute_batch(&format!(
+            "ALTER TABLE entries RENAME TO entries_v0;
+            DROP INDEX IF EXISTS entries_lookup;
+            {}
+            INSERT INTO entries (id, source_key, source_text, translation, target_language, style, model, glossary_version, created_at, last_used_at, hits)
+                SELECT id, source_key, source_text, translation, target_language, '', model, glossary_version, created_at, last_used_at, hits FROM entries_v0;
+            DROP TABLE entries_v0;",
+            CREATE_ENTRIES
+        ))
+        .map_err(to_error)?;
+    } else {
+        transaction.execute_batch(CREATE_ENTRIES).map_err(to_error)?;
+    }
+    transaction.execute_batch("CREATE INDEX IF NOT EXISTS entries_lookup ON entries (target_language, style, model, glossary_version);")
+        .map_err(to_error)?;
+    transaction.pragma_update(None, "user_version", SCHEMA_VERSION).map_err(to_error)?;
+    transaction.commit().map_err(to_error)
+}
+
+/// The database connection, opened and migrated once at startup and kept in Tauri state.
+/// Queries run on the blocking thread pool so fuzzy scans never stall the async runtime.
+pub struct TranslationMemory {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl TranslationMemory {
+    pub fn open(app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let connection = Connection::open(database_path(app_handle)?)
+            .map_err(|e| format!("Failed to open translation memory: {}", e))?;
+        // Another running instance of the app may be writing at the same time
+        connection.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| format!("Failed to configure translation memory: {}", e))?;
+        Self::new(connection)
+    }
+
+    fn new(mut connection: Connection) -> Result<Self, String> {
+        migrate(&mut connection)?;
+        Ok(TranslationMemory {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    async fn run<T, F>(&self, query: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, String> + Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|_| "Failed to acquire translation memory lock")?;
+            query(&connection)
+        })
+        .await
+        .map_err(|e| format!("Translation memory query failed: {}", e))?
+    }
+
+    pub async fn lookup(&self, text: &str, key: &MemoryKey, fuzzy_threshold: f64) -> Result<Option<MemoryMatch>, String> {
+        let (text, key) = (text.to_string(), key.clone());
+        self.run(move |connection| lookup(connection, &text, &key, fuzzy_threshold)).await
+    }
+
+    pub async fn store(&self, text: &str, translation: &str, key: &MemoryKey, max_entries: Option<u32>) -> Result<(), String> {
+        let (text, translation, key) = (text.to_string(), translation.to_string(), key.clone());
+        self.run(move |connection| store(connection, &text, &translation, &key, max_entries)).await
+    }
+}
+
+const ENTRY_COLUMNS: &str = "id, source_text, translation, target_language, style, model, glossary_version, created_at, last_used_at, hits";
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<MemoryEntry> {
+    Ok(MemoryEntry {
+        id: row.get(0)?,
+        source_text: row.get(1)?,
+        translation: row.get(2)?,
+        target_language: row.get(3)?,
+        style: row.get(4)?,
+        model: row.get(5)?,
+        glossary_version: row.get(6)?,
+        created_at: row.get(7)?,
+        last_used_at: row.get(8)?,
+        hits: row.get(9)?,
+    })
+}
+
+/// The exact match for `text`, or else the most similar entry above `fuzzy_threshold`.
+/// Exact hits are counted so frequently reused entries survive the size limit.
+fn lookup(connection: &Connection, text: &str, key: &MemoryKey, fuzzy_threshold: f64) -> Result<Option<MemoryMatch>, String> {
+    let source_key = normalize(text);
+
+    let exact = connection.query_row(
+        &format!("SELECT {} FROM entries WHERE source_key = ?1 AND target_language = ?2 AND style = ?3 AND model = ?4 AND glossary_version = ?5", ENTRY_COLUMNS),
+        params![source_key, key.target_language, key.style, key.model, key.glossary_version],
+        entry_from_row,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to query translation memory: {}", e))?;
+
+    if let Some(entry) = exact {
+        connection.execute(
+            "UPDATE entries SET hits = hits + 1, last_used_at = ?1 WHERE id = ?2",
+            params![chrono::Utc::now().to_rfc3339(), entry.id],
+        )
+        .map_err(|e| format!("Failed to update translation memory: {}", e))?;
+        return Ok(Some(MemoryMatch { entry, score: 1.0 }));
+    }
+
+    let length = source_key.chars().count() as f64;
+    let mut statement = connection.prepare(&format!(
+        "SELECT {}, source_key FROM entries WHERE target_language = ?1 AND style = ?2 AND model = ?3 AND glossary_version = ?4 AND length(source_key) BETWEEN ?5 AND ?6",
+        ENTRY_COLUMNS
+    ))
+    .map_err(|e| format!("Failed to query translation memory: {}", e))?;
+    let candidates = statement.query_map(
+        params![
+            key.target_language,
+            key.style,
+            key.model,
+            key.glossary_version,
+            (length * FUZZY_LENGTH_RATIO).floor() as i64,
+            (length / FUZZY_LENGTH_RATIO).ceil() as i64,
+        ],
+        |row| Ok((entry_from_row(row)?, row.get::<_, String>(10)?)),
+    )
+    .map_err(|e| format!("Failed to query translation memory: {}", e))?;
+
+    let mut best: Option<MemoryMatch> = None;
+    for candidate in candidates {
+        let (entry, candidate_key) = candidate.map_err(|e| format!("Failed to read translation memory: {}", e))?;
+        let score = strsim::normalized_levenshtein(&source_key, &candidate_key);
+        if score >= fuzzy_threshold && best.as_ref().is_none_or(|best| score > best.score) {
+            best = Some(MemoryMatch { entry, score });
+        }
+    }
+    Ok(best)
+}
+
+/// Stores (or refreshes) a translation and trims the memory to `max_entries`.
+fn store(connection: &Connection, text: &str, translation: &str, key: &MemoryKey, max_entries: Option<u32>) -> Result<(), String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    connection.execute(
+        "INSERT INTO entries (source_key, source_text, translation, target_language, style, model, glossary_version, created_at, last_used_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+         ON CONFLICT (source_key, target_language, style, model, glossary_version)
+         DO UPDATE SET translation = excluded.translation, last_used_at = excluded.last_used_at",
+        params![normalize(text), text, translation, key.target_language, key.style, key.model, key.glossary_version, now],
+    )
+    .map_err(|e| format!("Failed to store translation: {}", e))?;
+
+    if let Some(max_entries) = max_entries {
+        connection.execute(
+            "DELETE FROM entries WHERE id NOT IN (SELECT id FROM entries ORDER BY last_used_at DESC LIMIT ?1)",
+            params![max_entries],
+        )
+        .map_err(|e| format!("Failed to trim translation memory: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Prompt addition that shows the model an earlier translation of a similar text.
+pub fn reference_section(fuzzy: &MemoryMatch) -> String {
+    format!(
+        "A similar text was translated before. Reuse its wording and terminology where the texts agree.\nEarlier source: {}\nEarlier translation: {}",
+        fuzzy.entry.source_text, fuzzy.entry.translation
+    )
+}
+
+fn list(connection: &Connection, query: Option<String>, target_language: Option<String>, limit: u32, offset: u32) -> Result<Vec<MemoryEntry>, String> {
+    let pattern = query.map(|query| format!("%{}%", query.trim()));
+    let mut statement = connection.prepare(&format!(
+        "SELECT {} FROM entries
+         WHERE (?1 IS NULL OR source_text LIKE ?1 OR translation LIKE ?1) AND (?2 IS NULL OR target_language = ?2)
+         ORDER BY last_used_at DESC LIMIT ?3 OFFSET ?4",
+        ENTRY_COLUMNS
+    ))
+    .map_err(|e| format!("Failed to query translation memory: {}", e))?;
+
+    let entries = statement.query_map(params![pattern, target_language, limit, offset], entry_from_row)
+        .map_err(|e| format!("Failed to query translation memory: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read translation memory: {}", e))?;
+    Ok(entries)
+}
+
+fn purge(connection: &Connection, ids: Option<Vec<i64>>, target_language: Option<String>, older_than_days: Option<u32>) -> Result<usize, String> {
+    match ids {
+        Some(ids) => {
+            let mut deleted = 0;
+            for id in ids {
+                deleted += connection.execute("DELETE FROM entries WHERE id = ?1", params![id])
+                    .map_err(|e| format!("Failed to purge translation memory: {}", e))?;
+            }
+            Ok(deleted)
+        }
+        None => {
+            let cutoff = older_than_days.map(|days| (chrono::Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339());
+            connection.execute(
+                "DELETE FROM entries WHERE (?1 IS NULL OR target_language = ?1) AND (?2 IS NULL OR last_used_at < ?2)",
+                params![target_language, cutoff],
+            )
+            .map_err(|e| format!("Failed to purge translation memory: {}", e))
+        }
+    }
+}
+
+/// The managed translation memory, unless it failed to open at startup.
+pub fn state(app_handle: &tauri::AppHandle) -> Result<tauri::State<'_, TranslationMemory>, String> {
+    app_handle.try_state::<TranslationMemory>()
+        .ok_or_else(|| "Translation memory is unavailable".to_string())
+}
+
+/// Lists entries, most recently used first. `query` filters on source or translation text.
+#[tauri::command]
+pub async fn list_translation_memory(
+    app_handle: tauri::AppHandle,
+    query: Option<String>,
+    target_language: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<MemoryEntry>, String> {
+    state(&app_handle)?
+        .run(move |connection| list(connection, query, target_language, limit.unwrap_or(100), offset.unwrap_or(0)))
+        .await
+}
+
+/// Deletes the given entries, or everything matching the filters; with no arguments the whole memory.
+/// Returns the number of deleted entries.
+#[tauri::command]
+pub async fn purge_translation_memory(
+    app_handle: tauri::AppHandle,
+    ids: Option<Vec<i64>>,
+    target_language: Option<String>,
+    older_than_days: Option<u32>,
+) -> Result<usize, String> {
+    let deleted = state(&app_handle)?
+        .run(move |connection| purge(connection, ids, target_language, older_than_days))
+        .await?;
+    println!("🧹 Purged {} translation memory entries", deleted);
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_version_0_databases() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(
+            "CREATE TABLE entries (
+                id INTEGER PRIMARY KEY,
+                source_key TEXT NOT NULL,
+                source_text TEXT NOT NULL,
+                translation TEXT NOT NULL,
+                target_language TEXT NOT NULL COLLATE NOCASE,
+                model TEXT NOT NULL,
+                glossary_version TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                last_used_at TEXT NOT NULL,
+                hits INTEGER NOT NULL DEFAULT 0,
+                UNIQUE (source_key, target_language, model, glossary_version)
+            );
+            CREATE INDEX entries_lookup ON entries (target_language, model, glossary_version);
+            INSERT INTO entries (source_key, source_text, translation, target_language, model, glossary_version, created_at, last_used_at, hits)
+                VALUES ('Hello', 'Hello', 'Hallo', 'German', 'gpt-4o-mini', 'v1', 'now', 'now', 3);",
+        )
+        .unwrap();
+
+        migrate(&mut connection).unwrap();
+        // Running again on a current database is a no-op
+        migrate(&mut connection).unwrap();
+
+        let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        let entry = connection.query_row(&format!("SELECT {} FROM entries", ENTRY_COLUMNS), [], entry_from_row).unwrap();
+        assert_eq!(entry.translation, "Hallo");
+        assert_eq!(entry.style, "");
+        assert_eq!(entry.hits, 3);
+
+        // The same text in another style is a separate entry under the new unique key
+        connection.execute(
+            "INSERT INTO entries (source_key, source_text, translation, target_language, style, model, glossary_version, created_at, last_used_at)
+                VALUES ('Hello', 'Hello', 'Guten Tag', 'German', 'formal', 'gpt-4o-mini', 'v1', 'now', 'now')",
+            [],
+        )
+        .unwrap();
+    }
+
+    fn memory() -> Connection {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        connection
+    }
+
+    fn key(target_language: &str, style: &str, glossary_version: &str) -> MemoryKey {
+        MemoryKey {
+            target_language: target_language.to_string(),
+            style: style.to_string(),
+            model: "gpt-4o-mini".to_string(),
+            glossary_version: glossary_version.to_string(),
+        }
+    }
+
+    const SOURCE: &str = "Please send me the quarterly report by Friday.";
+
+    #[test]
+    fn exact_hits_ignore_whitespace_and_are_counted() {
+        let connection = memory();
+        let german = key("German", "", "v1");
+        store(&connection, SOURCE, "Bitte senden Sie mir den Quartalsbericht bis Freitag.", &german, None).unwrap();
+
+        let rewrapped = "Please send me the quarterly\n  report by Friday.";
+        let hit = lookup(&connection, rewrapped, &german, 0.85).unwrap().unwrap();
+        assert_eq!(hit.score, 1.0);
+        assert_eq!(hit.entry.translation, "Bitte senden Sie mir den Quartalsbericht bis Freitag.");
+        assert_eq!(hit.entry.source_text, SOURCE);
+
+        // Language names compare case-insensitively
+        let hit = lookup(&connection, SOURCE, &key("german", "", "v1"), 0.85).unwrap().unwrap();
+        assert_eq!(hit.entry.hits, 1);
+        assert_eq!(lookup(&connection, SOURCE, &german, 0.85).unwrap().unwrap().entry.hits, 2);
+    }
+
+    #[test]
+    fn storing_again_replaces_the_translation() {
+        let connection = memory();
+        let german = key("German", "", "v1");
+        store(&connection, SOURCE, "Erste Fassung", &german, None).unwrap();
+        store(&connection, SOURCE, "Zweite Fassung", &german, None).unwrap();
+
+        assert_eq!(list(&connection, None, None, 100, 0).unwrap().len(), 1);
+        assert_eq!(lookup(&connection, SOURCE, &german, 0.85).unwrap().unwrap().entry.translation, "Zweite Fassung");
+    }
+
+    #[test]
+    fn fuzzy_matches_respect_the_threshold() {
+        let connection = memory();
+        let german = key("German", "", "v1");
+        store(&connection, SOURCE, "Bitte senden Sie mir den Quartalsbericht bis Freitag.", &german, None).unwrap();
+
+        let similar = "Please send me the quarterly report by Monday.";
+        let score = strsim::normalized_levenshtein(SOURCE, similar);
+        let fuzzy = lookup(&connection, similar, &german, 0.85).unwrap().unwrap();
+        assert!(fuzzy.score < 1.0);
+        assert!((fuzzy.score - score).abs() < 1e-9);
+        // Fuzzy matches are references, not reuse, so they don't count as hits
+        assert_eq!(fuzzy.entry.hits, 0);
+
+        assert!(lookup(&connection, similar, &german, score + 0.01).unwrap().is_none());
+        assert!(lookup(&connection, "Completely unrelated text of similar size!!!", &german, 0.5).unwrap().is_none());
+    }
+
+    #[test]
+    fn entries_are_keyed_by_language_style_and_glossary_version() {
+        let connection = memory();
+        store(&connection, SOURCE, "Schick mir bitte den Quartalsbericht bis Freitag.", &key("German", "informal", "v1"), None).unwrap();
+        store(&connection, SOURCE, "Bitte senden Sie mir den Quartalsbericht bis Freitag.", &key("German", "formal", "v1"), None).unwrap();
+
+        let formal = lookup(&connection, SOURCE, &key("German", "formal", "v1"), 0.85).unwrap().unwrap();
+        assert_eq!(formal.entry.translation, "Bitte senden Sie mir den Quartalsbericht bis Freitag.");
+        let informal = lookup(&connection, SOURCE, &key("German", "informal", "v1"), 0.85).unwrap().unwrap();
+        assert_eq!(informal.entry.translation, "Schick mir bitte den Quartalsbericht bis Freitag.");
+
+        assert!(lookup(&connection, SOURCE, &key("German", "", "v1"), 0.5).unwrap().is_none());
+        assert!(lookup(&connection, SOURCE, &key("German", "formal", "v2"), 0.5).unwrap().is_none());
+        assert!(lookup(&connection, SOURCE, &key("French", "formal", "v1"), 0.5).unwrap().is_none());
+        let other_model = MemoryKey { model: "gpt-4o".to_string(), ..key("German", "formal", "v1") };
+        assert!(lookup(&connection, SOURCE, &other_model, 0.5).unwrap().is_none());
+    }
+
+    #[test]
+    fn store_trims_to_the_most_recently_used_entries() {
+        let connection = memory();
+        let german = key("German", "", "v1");
+        for text in ["one", "two", "three"] {
+            store(&connection, text, text, &german, Some(2)).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let remaining: Vec<String> = list(&connection, None, None, 100, 0).unwrap().into_iter().map(|entry| entry.source_text).collect();
+        assert_eq!(remaining, vec!["three".to_string(), "two".to_string()]);
+    }
+
+    #[test]
+    fn purge_filters_by_id_language_and_age() {
+        let connection = memory();
+        store(&connection, "Hello", "Hallo", &key("German", "", "v1"), None).unwrap();
+        store(&connection, "Goodbye", "Tschüss", &key("German", "", "v1"), None).unwrap();
+        store(&connection, "Hello", "Bonjour", &key("French", "", "v1"), None).unwrap();
+        connection.execute("UPDATE entries SET last_used_at = '2000-01-01T00:00:00+00:00' WHERE translation = 'Tschüss'", []).unwrap();
+
+        assert_eq!(list(&connection, Some("hallo".to_string()), None, 100, 0).unwrap().len(), 1);
+        assert_eq!(list(&connection, None, Some("german".to_string()), 100, 0).unwrap().len(), 2);
+
+        // Only German entries unused for 30 days
+        assert_eq!(purge(&connection, None, Some("German".to_string()), Some(30)).unwrap(), 1);
+        assert_eq!(purge(&connection, None, Some("Spanish".to_string()), None).unwrap(), 0);
+
+        let french = list(&connection, None, Some("French".to_string()), 100, 0).unwrap();
+        assert_eq!(purge(&connection, Some(vec![french[0].id, 9999]), None, None).unwrap(), 1);
+
+        assert_eq!(purge(&connection, None, None, None).unwrap(), 1);
+        assert!(list(&connection, None, None, 100, 0).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn managed_memory_runs_queries_off_the_runtime() {
+        let memory = TranslationMemory::new(Connection::open_in_memory().unwrap()).unwrap();
+        let german = key("German", "", "v1");
+        memory.store("Hello", "Hallo", &german, None).await.unwrap();
+        let hit = memory.lookup("Hello", &german, 0.85).await.unwrap().unwrap();
+        assert_eq!(hit.entry.translation, "Hallo");
+    }
+
+    #[test]
+    fn creates_new_databases() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+
+        let has_style: bool = connection.query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info('entries') WHERE name = 'style')",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+        assert!(has_style);
+    }
+}
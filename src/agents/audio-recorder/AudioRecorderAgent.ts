@@ -45,6 +45,7 @@ export class AudioRecorderAgent extends BaseAgent {
   }
 
   private async startRecording(): Promise<void> {
+    // Microphone, silence warning and failover preferences come from the backend settings
     console.log('🔴 Starting audio recording...');
     await this.audioService.startRecording();
     this.isRecording = true;
@@ -52,15 +53,22 @@ export class AudioRecorderAgent extends BaseAgent {
 
   private async stopRecordingAndTranscribe(): Promise<string> {
     console.log('⏹️ Stopping recording and transcribing...');
-    const audioData = await this.audioService.stopRecording();
+    const recording = await this.audioService.stopRecording();
     this.isRecording = false;
 
-    if (!audioData || audioData.length === 0) {
+    // With a microphone track, speakers are labeled; both transcripts mark where the recording was paused
+    if (recording.microphoneData && recording.format) {
+      console.log('🤖 Transcribing audio with speaker labels...');
+      const result = await this.audioService.transcribeWithSpeakers(recording);
+      return result.transcription || '';
+    }
+
+    if (!recording.audioData || recording.audioData.length === 0) {
       throw new Error('No audio data recorded');
     }
 
     console.log('🤖 Transcribing audio...');
-    const transcription = await this.audioService.transcribeAudio(audioData);
+    const transcription = await this.audioService.transcribeAudio(recording.audioData, recording.recordingId, recording.format, recording.pauses);
     
     if (!transcription || transcription.trim().length === 0) {
       return 'No speech detected in the recorded audio. Try recording when someone is speaking or increase recording duration.';
@@ -1,9 +1,18 @@
 import { BaseAgent, type AgentConfig, type AgentContext, type AgentResult } from '../../types/Agent.js';
+import type { ResponseMetadata } from '../../services/TranslationService.js';
+import type { SummaryMode, SummaryOptions } from '../../services/SettingsService.js';
+
+interface SummarizeResponse {
+  summary: string;
+  mode: SummaryMode;
+  metadata: ResponseMetadata;
+}
 
 export class ClipboardSummarizerAgent extends BaseAgent {
-  private apiKey: string;
+  // Falls back to the summary style saved in the settings
+  private options?: Partial<SummaryOptions>;
 
-  constructor(apiKey: string = 'dummy') {
+  constructor() {
     const config: AgentConfig = {
       name: 'Clipboard Summarizer',
       description: 'Summarize clipboard text content using AI',
@@ -12,7 +21,10 @@ export class ClipboardSummarizerAgent extends BaseAgent {
     };
 
     super(config);
-    this.apiKey = apiKey;
+  }
+
+  public setOptions(options?: Partial<SummaryOptions>): void {
+    this.options = options;
   }
 
   public async execute(context: AgentContext): Promise<AgentResult> {
@@ -46,18 +58,13 @@ export class ClipboardSummarizerAgent extends BaseAgent {
     try {
       const { invoke } = await import('@tauri-apps/api/core');
       
-      const result = await invoke('summarize_text', {
-        text: text,
-        apiKey: this.apiKey
-      });
+      // The API key is resolved by the backend
+      const result = await invoke<SummarizeResponse>('summarize_text', { request: { text, options: this.options } });
+      console.log(`📝 Summarized (${result.mode}) with the ${result.metadata.credential} key (${result.metadata.model})`);
 
-      return result as string;
+      return result.summary;
     } catch (error) {
       throw new Error(`Failed to summarize text: ${error}`);
     }
   }
-
-  public updateApiKey(newApiKey: string): void {
-    this.apiKey = newApiKey;
-  }
 }
\ No newline at end of file
@@ -0,0 +1,65 @@
+import { BaseAgent, type AgentConfig, type AgentContext, type AgentResult } from '../../types/Agent.js';
+import type { ResponseMetadata } from '../../services/TranslationService.js';
+
+export type ExplainTask = 'explain' | 'find_bugs' | 'doc_comments';
+
+export interface DetectedCode {
+  kind: 'code' | 'stack_trace';
+  /** Markdown fence tag such as "rust" or "python" */
+  language?: string | null;
+}
+
+export interface ExplainResponse {
+  markdown: string;
+  task: ExplainTask;
+  detected: DetectedCode;
+  metadata: ResponseMetadata;
+}
+
+export class CodeExplainerAgent extends BaseAgent {
+  private task: ExplainTask = 'explain';
+
+  constructor() {
+    const config: AgentConfig = {
+      name: 'Code Explainer',
+      description: 'Explain, review or document code and stack traces from the clipboard',
+      shortcut: 'cmd+e',
+      enabled: true
+    };
+
+    super(config);
+  }
+
+  public setTask(task: ExplainTask): void {
+    this.task = task;
+  }
+
+  public async execute(context: AgentContext): Promise<AgentResult> {
+    const { input } = context;
+
+    if (!input || input.trim().length === 0) {
+      return {
+        success: false,
+        error: 'No code found in clipboard to explain'
+      };
+    }
+
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+
+      // The language is detected and the API key resolved by the backend
+      const result = await invoke<ExplainResponse>('explain_code', { request: { text: input, task: this.task } });
+      console.log(`🧑‍💻 ${result.task} for ${result.detected.language ?? 'unknown language'} with the ${result.metadata.credential} key (${result.metadata.model})`);
+
+      return {
+        success: true,
+        output: result.markdown
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error)
+      };
+    }
+  }
+}
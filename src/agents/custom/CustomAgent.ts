@@ -0,0 +1,74 @@
+import { invoke } from '@tauri-apps/api/core';
+import { BaseAgent, type AgentConfig, type AgentContext, type AgentResult } from '../../types/Agent.js';
+import type { PromptTemplate } from '../../services/PromptService.js';
+import type { ResponseMetadata } from '../../services/TranslationService.js';
+
+export type InputSource = 'clipboard' | 'selection' | 'audio';
+export type OutputSink = 'window' | 'clipboard' | 'notification';
+
+/** An agent defined in `agents/<id>.json` in the app config directory */
+export interface CustomAgentDefinition {
+  id: string;
+  name: string;
+  description: string;
+  shortcut?: string | null;
+  prompt: PromptTemplate;
+  model?: string | null;
+  temperature?: number | null;
+  max_tokens?: number | null;
+  input: InputSource;
+  output: OutputSink;
+}
+
+export interface CustomAgentList {
+  agents: CustomAgentDefinition[];
+  /** One message per agent file that could not be loaded */
+  errors: string[];
+}
+
+export interface CustomAgentRun {
+  agent: string;
+  output: string;
+  sink: OutputSink;
+  metadata: ResponseMetadata;
+}
+
+export async function listCustomAgents(): Promise<CustomAgentList> {
+  return invoke<CustomAgentList>('list_custom_agents');
+}
+
+/** Runs a custom agent in the backend, which reads its input and delivers the output to its sink */
+export class CustomAgent extends BaseAgent {
+  public readonly definition: CustomAgentDefinition;
+
+  constructor(definition: CustomAgentDefinition) {
+    const config: AgentConfig = {
+      name: definition.name,
+      description: definition.description,
+      shortcut: definition.shortcut ?? '',
+      enabled: true
+    };
+
+    super(config);
+    this.definition = definition;
+  }
+
+  public async execute(context: AgentContext): Promise<AgentResult> {
+    try {
+      // Only selection agents take their input from the caller
+      const input = this.definition.input === 'selection' && context.input ? context.input : null;
+      const result = await invoke<CustomAgentRun>('run_custom_agent', { id: this.definition.id, input });
+      console.log(`🤖 ${this.definition.name} finished with the ${result.metadata.credential} key (${result.metadata.model})`);
+
+      return {
+        success: true,
+        output: result.output
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error)
+      };
+    }
+  }
+}
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+import { BaseAgent, type AgentConfig, type AgentContext, type AgentResult } from '../../types/Agent.js';
+
+export class MeetingNotesAgent extends BaseAgent {
+  constructor() {
+    const config: AgentConfig = {
+      name: 'Meeting Notes',
+      description: 'Turn the last recording into Markdown meeting notes',
+      shortcut: 'cmd+m',
+      enabled: true
+    };
+
+    super(config);
+  }
+
+  public async execute(_context: AgentContext): Promise<AgentResult> {
+    try {
+      console.log('📝 Generating meeting notes from the last recording...');
+
+      // The backend transcribes, streams the notes into their own window and saves them
+      await invoke('generate_meeting_notes');
+
+      return {
+        success: true,
+        output: 'Meeting notes generated'
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error)
+      };
+    }
+  }
+}
@@ -0,0 +1,42 @@
+import { BaseAgent, type AgentConfig, type AgentContext, type AgentResult } from '../../types/Agent.js';
+import { PipelineService, type Pipeline } from '../../services/PipelineService.js';
+
+/** Runs a saved pipeline in the pipeline window */
+export class PipelineAgent extends BaseAgent {
+  public readonly id: string;
+  public readonly pipeline: Pipeline;
+  private pipelineService = new PipelineService();
+
+  constructor(id: string, pipeline: Pipeline) {
+    const config: AgentConfig = {
+      name: pipeline.name,
+      description: pipeline.description,
+      shortcut: pipeline.shortcut ?? '',
+      enabled: true
+    };
+
+    super(config);
+    this.id = id;
+    this.pipeline = pipeline;
+  }
+
+  public async execute(context: AgentContext): Promise<AgentResult> {
+    try {
+      // Without input the backend reads the clipboard, unless the pipeline starts by transcribing
+      await this.pipelineService.showPipelineWindow({
+        pipeline: this.id,
+        input: context.input || undefined
+      });
+
+      return {
+        success: true,
+        output: 'Pipeline window opened'
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error)
+      };
+    }
+  }
+}
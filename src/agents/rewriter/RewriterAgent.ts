@@ -0,0 +1,64 @@
+import { BaseAgent, type AgentConfig, type AgentContext, type AgentResult } from '../../types/Agent.js';
+import type { ResponseMetadata } from '../../services/TranslationService.js';
+
+export type RewriteMode = 'fix_grammar' | 'shorten' | 'formal' | 'friendly';
+
+export interface DiffSpan {
+  op: 'equal' | 'insert' | 'delete';
+  text: string;
+}
+
+export interface RewriteResponse {
+  rewritten: string;
+  mode: RewriteMode;
+  diff: DiffSpan[];
+  metadata: ResponseMetadata;
+}
+
+export class RewriterAgent extends BaseAgent {
+  private mode: RewriteMode = 'fix_grammar';
+
+  constructor() {
+    const config: AgentConfig = {
+      name: 'Rewriter',
+      description: 'Fix the grammar of clipboard text or make it shorter, more formal or friendlier',
+      shortcut: 'cmd+g',
+      enabled: true
+    };
+
+    super(config);
+  }
+
+  public setMode(mode: RewriteMode): void {
+    this.mode = mode;
+  }
+
+  public async execute(context: AgentContext): Promise<AgentResult> {
+    const { input } = context;
+
+    if (!input || input.trim().length === 0) {
+      return {
+        success: false,
+        error: 'No text found in clipboard to rewrite'
+      };
+    }
+
+    try {
+      const { invoke } = await import('@tauri-apps/api/core');
+
+      // The API key is resolved by the backend
+      const result = await invoke<RewriteResponse>('rewrite_text', { request: { text: input, mode: this.mode } });
+      console.log(`✍️ Rewrote (${result.mode}) with the ${result.metadata.credential} key (${result.metadata.model})`);
+
+      return {
+        success: true,
+        output: result.rewritten
+      };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : String(error)
+      };
+    }
+  }
+}
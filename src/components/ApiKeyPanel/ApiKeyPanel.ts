@@ -0,0 +1,121 @@
+import { ApiKeyService, type ApiKeyStatus } from '../../services/ApiKeyService.js';
+import { SettingsService } from '../../services/SettingsService.js';
+
+const STORAGE_LABELS: Record<NonNullable<ApiKeyStatus['storage']>, string> = {
+  keyring: 'system keyring',
+  obfuscated_file: 'obfuscated file, not secure',
+  environment: 'environment',
+};
+
+// Offered even before a key is stored under them
+const SUGGESTED_NAMES = ['personal', 'team', 'project'];
+
+export class ApiKeyPanel {
+  private element: HTMLElement;
+  private apiKeyService = new ApiKeyService();
+  private settingsService = new SettingsService();
+  private statusText: HTMLElement;
+  private nameSelect: HTMLSelectElement;
+  private input: HTMLInputElement;
+  private credentials: ApiKeyStatus[] = [];
+
+  constructor() {
+    this.element = this.createElement();
+    this.statusText = this.element.querySelector('.api-key-panel__status') as HTMLElement;
+    this.nameSelect = this.element.querySelector('.api-key-panel__name') as HTMLSelectElement;
+    this.input = this.element.querySelector('.api-key-panel__input') as HTMLInputElement;
+    this.setupEventListeners();
+    this.refresh();
+  }
+
+  private createElement(): HTMLElement {
+    const panel = document.createElement('div');
+    panel.className = 'api-key-panel';
+    panel.innerHTML = `
+      <span class="api-key-panel__status status-text">Checking API key...</span>
+      <select class="api-key-panel__name" title="Active credential"></select>
+      <input class="api-key-panel__input" type="password" placeholder="sk-..." autocomplete="off" />
+      <button class="api-key-panel__btn" data-action="save">Save</button>
+      <button class="api-key-panel__btn" data-action="test">Test</button>
+      <button class="api-key-panel__btn" data-action="clear">Clear</button>
+    `;
+    return panel;
+  }
+
+  private setupEventListeners(): void {
+    // Picking a name makes it the active credential for every request
+    this.nameSelect.addEventListener('change', async () => {
+      try {
+        await this.settingsService.updateSettings({ active_credential: this.nameSelect.value });
+        await this.refresh();
+      } catch (error) {
+        this.statusText.textContent = `❌ ${error}`;
+      }
+    });
+
+    this.element.addEventListener('click', async (e) => {
+      if (!(e.target instanceof HTMLButtonElement)) {
+        return;
+      }
+
+      const name = this.nameSelect.value;
+      try {
+        switch (e.target.dataset.action) {
+          case 'save': {
+            const apiKey = this.input.value.trim();
+            if (!apiKey) {
+              return;
+            }
+            this.render(await this.apiKeyService.setApiKey(name, apiKey));
+            this.input.value = '';
+            break;
+          }
+          case 'test': {
+            const result = await this.apiKeyService.testApiKey(name);
+            this.statusText.textContent = result.success ? `✅ ${name} key works` : `❌ ${result.error}`;
+            break;
+          }
+          case 'clear':
+            this.render(await this.apiKeyService.clearApiKey(name));
+            break;
+        }
+      } catch (error) {
+        this.statusText.textContent = `❌ ${error}`;
+      }
+    });
+  }
+
+  private async refresh(): Promise<void> {
+    try {
+      this.render(await this.apiKeyService.listCredentials());
+    } catch (error) {
+      console.error('Failed to load API key status:', error);
+    }
+  }
+
+  private render(credentials: ApiKeyStatus[]): void {
+    this.credentials = credentials;
+    const names = [...new Set([...credentials.map((credential) => credential.name), ...SUGGESTED_NAMES])];
+    const active = credentials.find((credential) => credential.active);
+
+    this.nameSelect.innerHTML = names
+      .map((name) => {
+        const configured = this.credentials.some((credential) => credential.name === name && credential.configured);
+        return `<option value="${name}">${name}${configured ? '' : ' (empty)'}</option>`;
+      })
+      .join('');
+    if (active) {
+      this.nameSelect.value = active.name;
+    }
+
+    // Without a keyring the key file can be read by anyone who can read the app data directory
+    const icon = active?.storage === 'obfuscated_file' ? '⚠️' : '🔐';
+    this.statusText.textContent = active?.configured && active.storage
+      ? `${icon} ${active.name} key …${active.hint} (${STORAGE_LABELS[active.storage]})`
+      : `⚠️ No API key for ${active?.name ?? 'personal'}`;
+  }
+
+  public getElement(): HTMLElement {
+    return this.element;
+  }
+}
@@ -1,12 +1,19 @@
 import { FeatureSection, type FeatureSectionData } from './FeatureSection/FeatureSection.js';
 import { MonitorPage } from './MonitorPage/MonitorPage.js';
+import { ApiKeyPanel } from './ApiKeyPanel/ApiKeyPanel.js';
 import { AgentManager } from '../services/AgentManager.js';
 import { ShortcutManager } from '../services/ShortcutManager.js';
 import { TranslationService } from '../services/TranslationService.js';
 import { AudioRecordingService } from '../services/AudioRecordingService.js';
+import { SettingsService } from '../services/SettingsService.js';
 import { TranslatorAgent } from '../agents/translator/TranslatorAgent.js';
 import { AudioRecorderAgent } from '../agents/audio-recorder/AudioRecorderAgent.js';
 import { ClipboardSummarizerAgent } from '../agents/clipboard-summarizer/ClipboardSummarizerAgent.js';
+import { RewriterAgent } from '../agents/rewriter/RewriterAgent.js';
+import { CodeExplainerAgent } from '../agents/code-explainer/CodeExplainerAgent.js';
+import { MeetingNotesAgent } from '../agents/meeting-notes/MeetingNotesAgent.js';
+import { CustomAgent, listCustomAgents } from '../agents/custom/CustomAgent.js';
+import { PipelineAgent } from '../agents/pipeline/PipelineAgent.js';
 
 export class App {
   private container: HTMLElement;
@@ -26,6 +33,8 @@ export class App {
   }
 
   private async init(): Promise<void> {
+    await new SettingsService().migrateLocalStorage()
+      .catch((error) => console.error('Failed to migrate saved preferences:', error));
     this.createNavigation();
     this.createFeaturesContainer();
     this.renderFeatures();
@@ -39,10 +48,9 @@ export class App {
     nav.innerHTML = `
       <button class="nav-btn active" data-view="features">Features</button>
       <button class="nav-btn" data-view="monitor">Monitor</button>
-      <div class="status-section">
-        <span class="status-text">OpenAI API integrated</span>
-      </div>
+      <div class="status-section"></div>
     `;
+    nav.querySelector('.status-section')?.appendChild(new ApiKeyPanel().getElement());
 
     nav.addEventListener('click', (e) => {
       if (e.target instanceof HTMLButtonElement && e.target.classList.contains('nav-btn')) {
@@ -85,6 +93,21 @@ export class App {
         title: 'Clipboard Summarizer',
         description: 'Summarize any text from your clipboard using AI. Copy text from articles, documents, or websites, then press cmd+s to get a concise summary.',
         shortcut: 'cmd+s'
+      },
+      {
+        title: 'Rewriter',
+        description: 'Fix the grammar of a Slack message or email in your clipboard, or make it shorter, more formal or friendlier. Press cmd+g and see every change highlighted against the original.',
+        shortcut: 'cmd+g'
+      },
+      {
+        title: 'Code Explainer',
+        description: 'Copy a code snippet or stack trace and press cmd+e. The language is detected automatically; get an explanation, a bug review or the code with doc comments added, with highlighted code blocks.',
+        shortcut: 'cmd+e'
+      },
+      {
+        title: 'Meeting Notes',
+        description: 'Turn your last recording into Markdown meeting notes with a summary, decisions, action items with owners and open questions. Notes are saved to disk automatically.',
+        shortcut: 'cmd+m'
       }
     ];
 
@@ -101,8 +124,8 @@ export class App {
       // Debug: List available audio devices
       await this.debugAudioDevices();
       
-      // Initialize translation service with dummy key (handled in backend)
-      const translationService = new TranslationService('dummy-key', 'English');
+      // API keys are resolved by the backend from the active credential
+      const translationService = new TranslationService('English');
       
       // Initialize audio recording service
       const audioRecordingService = new AudioRecordingService();
@@ -116,14 +139,32 @@ export class App {
       console.log(`🎤 Created audio recorder agent with shortcut: ${audioRecorderAgent.getShortcut()}`);
       
       // Create and register clipboard summarizer agent
-      const summarizerAgent = new ClipboardSummarizerAgent('dummy');
+      const summarizerAgent = new ClipboardSummarizerAgent();
       console.log(`📝 Created summarizer agent with shortcut: ${summarizerAgent.getShortcut()}`);
       
+      // Create and register rewriter agent
+      const rewriterAgent = new RewriterAgent();
+      console.log(`✍️ Created rewriter agent with shortcut: ${rewriterAgent.getShortcut()}`);
+      
+      // Create and register code explainer agent
+      const codeExplainerAgent = new CodeExplainerAgent();
+      console.log(`🧑‍💻 Created code explainer agent with shortcut: ${codeExplainerAgent.getShortcut()}`);
+      
+      // Create and register meeting notes agent
+      const meetingNotesAgent = new MeetingNotesAgent();
+      console.log(`📝 Created meeting notes agent with shortcut: ${meetingNotesAgent.getShortcut()}`);
+      
       this.agentManager.registerAgent(translatorAgent);
       this.agentManager.registerAgent(audioRecorderAgent);
       this.agentManager.registerAgent(summarizerAgent);
+      this.agentManager.registerAgent(rewriterAgent);
+      this.agentManager.registerAgent(codeExplainerAgent);
+      this.agentManager.registerAgent(meetingNotesAgent);
       console.log('📋 Registered all agents in manager');
 
+      await this.registerCustomAgents();
+      await this.registerPipelines();
+
       // Register global shortcuts
       console.log('⌨️ Registering global shortcuts...');
       await this.shortcutManager.registerAgentShortcuts();
@@ -135,6 +176,49 @@ export class App {
     }
   }
 
+  // Agents defined in the agents/ folder of the app config directory; built-in shortcuts win
+  private async registerCustomAgents(): Promise<void> {
+    try {
+      const { agents, errors } = await listCustomAgents();
+      errors.forEach((error) => console.warn(`⚠️ Custom agent not loaded: ${error}`));
+
+      for (const definition of agents) {
+        if (!definition.shortcut) {
+          continue;
+        }
+        if (this.agentManager.getAgent(definition.shortcut)) {
+          console.warn(`⚠️ Custom agent "${definition.name}" skipped: ${definition.shortcut} is already taken`);
+          continue;
+        }
+        this.agentManager.registerAgent(new CustomAgent(definition));
+        console.log(`🤖 Registered custom agent "${definition.name}" with shortcut: ${definition.shortcut}`);
+      }
+    } catch (error) {
+      console.error('❌ Failed to load custom agents:', error);
+    }
+  }
+
+  // Saved pipelines that have a shortcut; taken shortcuts are skipped like for custom agents
+  private async registerPipelines(): Promise<void> {
+    try {
+      const { pipelines } = await new SettingsService().getSettings();
+
+      for (const [id, pipeline] of Object.entries(pipelines)) {
+        if (!pipeline.shortcut) {
+          continue;
+        }
+        if (this.agentManager.getAgent(pipeline.shortcut)) {
+          console.warn(`⚠️ Pipeline "${pipeline.name}" skipped: ${pipeline.shortcut} is already taken`);
+          continue;
+        }
+        this.agentManager.registerAgent(new PipelineAgent(id, pipeline));
+        console.log(`🔗 Registered pipeline "${pipeline.name}" with shortcut: ${pipeline.shortcut}`);
+      }
+    } catch (error) {
+      console.error('❌ Failed to load pipelines:', error);
+    }
+  }
+
   private switchView(view: 'features' | 'monitor'): void {
     const navBtns = this.container.querySelectorAll('.nav-btn');
     navBtns.forEach(btn => btn.classList.remove('active'));
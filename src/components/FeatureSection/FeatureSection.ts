@@ -1,3 +1,5 @@
+import { SettingsService } from '../../services/SettingsService.js';
+
 export interface FeatureSectionData {
   title: string;
   description: string;
@@ -6,6 +8,7 @@ export interface FeatureSectionData {
 
 export class FeatureSection {
   private element: HTMLElement;
+  private settingsService = new SettingsService();
 
   constructor(data: FeatureSectionData, container: HTMLElement) {
     this.element = this.createElement(data);
@@ -74,14 +77,23 @@ export class FeatureSection {
   }
 
   private setSelectedLanguage(language: string): void {
-    localStorage.setItem('selected-language', language);
-    console.log(`🌍 Selected language changed to: ${language}`);
+    this.settingsService.updateSettings({ target_language: language })
+      .then(() => console.log(`🌍 Selected language changed to: ${language}`))
+      .catch((error) => console.error('Failed to save language preference:', error));
   }
 
   private loadLanguagePreference(selectElement: HTMLSelectElement): void {
-    const savedLanguage = localStorage.getItem('selected-language') || 'German';
-    selectElement.value = savedLanguage;
-    console.log(`🌍 Loaded language preference: ${savedLanguage}`);
+    this.settingsService.getSettings()
+      .then((settings) => {
+        selectElement.value = settings.target_language;
+        console.log(`🌍 Loaded language preference: ${settings.target_language}`);
+      })
+      .catch((error) => console.error('Failed to load language preference:', error));
+
+    // Keep the dropdown in sync when another window changes the setting
+    this.settingsService.onSettingsChanged((settings) => {
+      selectElement.value = settings.target_language;
+    }).catch((error) => console.error('Failed to listen for settings changes:', error));
   }
 
   public getElement(): HTMLElement {
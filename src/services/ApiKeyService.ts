@@ -0,0 +1,33 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface ApiKeyStatus {
+  name: string;
+  active: boolean;
+  configured: boolean;
+  storage?: 'keyring' | 'obfuscated_file' | 'environment';
+  hint?: string;
+}
+
+export interface ApiKeyTestResult {
+  success: boolean;
+  error?: string;
+}
+
+// Keys are handed to the backend once when saved; they are never read back into the webview
+export class ApiKeyService {
+  public async listCredentials(): Promise<ApiKeyStatus[]> {
+    return invoke<ApiKeyStatus[]>('list_credentials');
+  }
+
+  public async setApiKey(name: string, apiKey: string): Promise<ApiKeyStatus[]> {
+    return invoke<ApiKeyStatus[]>('set_api_key', { name, apiKey });
+  }
+
+  public async clearApiKey(name: string): Promise<ApiKeyStatus[]> {
+    return invoke<ApiKeyStatus[]>('clear_api_key', { name });
+  }
+
+  public async testApiKey(name: string): Promise<ApiKeyTestResult> {
+    return invoke<ApiKeyTestResult>('test_api_key', { name });
+  }
+}
@@ -1,8 +1,110 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export interface CaptureFormat {
+  sample_rate: number;
+  channels: number;
+}
+
+export interface RecordingPause {
+  at_seconds: number;
+  duration_seconds: number;
+}
 
 export interface AudioRecordingResult {
   success: boolean;
   audio_data?: number[];
+  format?: CaptureFormat;
+  microphone_data?: number[];
+  microphone_format?: CaptureFormat;
+  pauses: RecordingPause[];
+  recording_id?: string;
+  error?: string;
+}
+
+export interface RecordedAudio {
+  audioData: number[];
+  format?: CaptureFormat;
+  microphoneData?: number[];
+  microphoneFormat?: CaptureFormat;
+  pauses: RecordingPause[];
+  recordingId?: string;
+}
+
+export interface RecordingEntry {
+  id: string;
+  started_at: string;
+  stopped_at: string;
+  duration_seconds: number;
+  device_name?: string;
+  microphone_device_name?: string;
+  storage_format: 'wav_float32' | 'wav_pcm16' | 'wav_speech16k';
+  system_file?: string;
+  system_format?: CaptureFormat;
+  microphone_file?: string;
+  microphone_format?: CaptureFormat;
+  pauses: RecordingPause[];
+  size_bytes: number;
+  transcription?: string;
+  transcription_error?: string;
+}
+
+export interface SpeakerSegment {
+  speaker: string;
+  source: 'microphone' | 'system';
+  start: number;
+  end: number;
+  text: string;
+}
+
+export interface ChannelLevel {
+  rms: number;
+  peak: number;
+}
+
+export interface AudioLevel {
+  source: 'microphone' | 'system';
+  channels: ChannelLevel[];
+  elapsed_seconds: number;
+  bytes_captured: number;
+}
+
+export interface SilenceWarning {
+  source: 'microphone' | 'system';
+  silent_seconds: number;
+}
+
+export interface DeviceChange {
+  added: string[];
+  removed: string[];
+  input_devices: string[];
+}
+
+export interface RecordingInterrupted {
+  source: 'microphone' | 'system';
+  device_name?: string;
+  error: string;
+  will_recover: boolean;
+}
+
+export interface RecordingRecovered {
+  source: 'microphone' | 'system';
+  device_name?: string;
+  gap_seconds: number;
+}
+
+export interface AudioProcessingConfig {
+  high_pass: boolean;
+  high_pass_hz: number;
+  noise_gate: boolean;
+  normalize: boolean;
+  target_lufs: number;
+}
+
+export interface DiarizedTranscriptionResult {
+  success: boolean;
+  segments?: SpeakerSegment[];
+  transcription?: string;
   error?: string;
 }
 
@@ -15,13 +117,13 @@ export interface TranscriptionResult {
 export class AudioRecordingService {
   private isRecording: boolean = false;
 
-  public async startRecording(): Promise<void> {
+  public async startRecording(includeMicrophone?: boolean, silenceWarningSeconds?: number, failover?: boolean): Promise<void> {
     if (this.isRecording) {
       throw new Error('Already recording');
     }
 
     try {
-      await invoke('start_audio_recording');
+      await invoke('start_audio_recording', { includeMicrophone, silenceWarningSeconds, failover });
       this.isRecording = true;
       console.log('✅ Audio recording started');
     } catch (error) {
@@ -29,7 +131,7 @@ export class AudioRecordingService {
     }
   }
 
-  public async stopRecording(): Promise<number[]> {
+  public async stopRecording(): Promise<RecordedAudio> {
     if (!this.isRecording) {
       throw new Error('Not currently recording');
     }
@@ -43,14 +145,48 @@ export class AudioRecordingService {
       }
 
       console.log(`✅ Audio recording stopped. Data length: ${result.audio_data?.length || 0}`);
-      return result.audio_data || [];
+      return {
+        audioData: result.audio_data || [],
+        format: result.format,
+        microphoneData: result.microphone_data,
+        microphoneFormat: result.microphone_format,
+        pauses: result.pauses || [],
+        recordingId: result.recording_id
+      };
     } catch (error) {
       this.isRecording = false;
       throw new Error(`Failed to stop recording: ${error}`);
     }
   }
 
-  public async transcribeAudio(audioData: number[]): Promise<string> {
+  public async pauseRecording(): Promise<void> {
+    if (!this.isRecording) {
+      throw new Error('Not currently recording');
+    }
+
+    try {
+      await invoke('pause_audio_recording');
+      console.log('⏸️ Audio recording paused');
+    } catch (error) {
+      throw new Error(`Failed to pause recording: ${error}`);
+    }
+  }
+
+  public async resumeRecording(): Promise<void> {
+    if (!this.isRecording) {
+      throw new Error('Not currently recording');
+    }
+
+    try {
+      await invoke('resume_audio_recording');
+      console.log('▶️ Audio recording resumed');
+    } catch (error) {
+      throw new Error(`Failed to resume recording: ${error}`);
+    }
+  }
+
+  /** `format` and `pauses` belong to `audioData`; the backend assumes the last recording's when omitted */
+  public async transcribeAudio(audioData: number[], recordingId?: string, format?: CaptureFormat, pauses?: RecordingPause[]): Promise<string> {
     if (!audioData || audioData.length === 0) {
       throw new Error('No audio data to transcribe');
     }
@@ -58,7 +194,10 @@ export class AudioRecordingService {
     try {
       console.log('🤖 Sending audio to OpenAI Whisper...');
       const result = await invoke<TranscriptionResult>('transcribe_audio', { 
-        audioData 
+        audioData,
+        format,
+        pauses,
+        recordingId
       });
 
       if (!result.success) {
@@ -72,6 +211,72 @@ export class AudioRecordingService {
     }
   }
 
+  public async transcribeWithSpeakers(recording: RecordedAudio, maxSpeakers?: number): Promise<DiarizedTranscriptionResult> {
+    if (!recording.format) {
+      throw new Error('Recording has no capture format');
+    }
+
+    try {
+      console.log('🗣️ Sending audio for speaker-labeled transcription...');
+      const result = await invoke<DiarizedTranscriptionResult>('transcribe_with_speakers', {
+        request: {
+          audio_data: recording.audioData,
+          format: recording.format,
+          microphone_data: recording.microphoneData,
+          microphone_format: recording.microphoneFormat,
+          pauses: recording.pauses,
+          max_speakers: maxSpeakers,
+          recording_id: recording.recordingId
+        }
+      });
+
+      if (!result.success) {
+        throw new Error(result.error || 'Transcription failed');
+      }
+
+      console.log(`✅ Transcription completed with ${result.segments?.length || 0} speaker segments`);
+      return result;
+    } catch (error) {
+      throw new Error(`Transcription failed: ${error}`);
+    }
+  }
+
+  public async onAudioLevel(callback: (level: AudioLevel) => void): Promise<UnlistenFn> {
+    return listen<AudioLevel>('audio-level', (event) => callback(event.payload));
+  }
+
+  public async onSilenceWarning(callback: (warning: SilenceWarning) => void): Promise<UnlistenFn> {
+    return listen<SilenceWarning>('audio-silence-warning', (event) => callback(event.payload));
+  }
+
+  public async onDeviceChanged(callback: (change: DeviceChange) => void): Promise<UnlistenFn> {
+    return listen<DeviceChange>('audio-device-changed', (event) => callback(event.payload));
+  }
+
+  public async onRecordingInterrupted(callback: (interruption: RecordingInterrupted) => void): Promise<UnlistenFn> {
+    return listen<RecordingInterrupted>('recording-interrupted', (event) => callback(event.payload));
+  }
+
+  public async onRecordingRecovered(callback: (recovery: RecordingRecovered) => void): Promise<UnlistenFn> {
+    return listen<RecordingRecovered>('recording-recovered', (event) => callback(event.payload));
+  }
+
+  public async listRecordings(): Promise<RecordingEntry[]> {
+    return invoke<RecordingEntry[]>('list_recordings');
+  }
+
+  public async retranscribeRecording(id: string, maxSpeakers?: number): Promise<DiarizedTranscriptionResult> {
+    return invoke<DiarizedTranscriptionResult>('retranscribe_recording', { id, maxSpeakers });
+  }
+
+  public async exportRecording(id: string, destination: string): Promise<string[]> {
+    return invoke<string[]>('export_recording', { id, destination });
+  }
+
+  public async deleteRecording(id: string): Promise<void> {
+    await invoke('delete_recording', { id });
+  }
+
   public isCurrentlyRecording(): boolean {
     return this.isRecording;
   }
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface GlossaryEntry {
+  source: string;
+  target: string;
+  language: string;
+}
+
+export interface Glossary {
+  source_language: string;
+  entries: GlossaryEntry[];
+  do_not_translate: string[];
+}
+
+export type GlossaryFormat = 'csv' | 'tbx';
+
+export class GlossaryService {
+  public async getGlossary(): Promise<Glossary> {
+    return invoke<Glossary>('get_glossary');
+  }
+
+  public async saveGlossary(glossary: Glossary): Promise<Glossary> {
+    return invoke<Glossary>('save_glossary', { glossary });
+  }
+
+  /** Merges a CSV or TBX file into the glossary; the format follows the file extension unless given. */
+  public async importGlossary(path: string, replace = false, format?: GlossaryFormat): Promise<Glossary> {
+    return invoke<Glossary>('import_glossary', { path, format, replace });
+  }
+
+  public async exportGlossary(path: string, format?: GlossaryFormat): Promise<void> {
+    return invoke('export_glossary', { path, format });
+  }
+}
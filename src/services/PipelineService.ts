@@ -0,0 +1,69 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { PromptTemplate } from './PromptService.js';
+import type { SummaryOptions } from './SettingsService.js';
+
+/** Each step receives the output of the previous one */
+export type PipelineStep =
+  | { type: 'transcribe' }
+  | { type: 'translate'; target_language?: string | null }
+  | { type: 'summarize'; options?: SummaryOptions | null }
+  | { type: 'prompt'; prompt: PromptTemplate; model?: string | null }
+  | { type: 'agent'; id: string };
+
+/** A pipeline saved under an id in the `pipelines` section of the settings */
+export interface Pipeline {
+  name: string;
+  description: string;
+  shortcut?: string | null;
+  steps: PipelineStep[];
+}
+
+export interface PipelineRequest {
+  /** Id of a saved pipeline */
+  pipeline?: string;
+  /** Steps to run instead of a saved pipeline */
+  steps?: PipelineStep[];
+  /** Input of the first step; the clipboard when omitted */
+  input?: string;
+  credential?: string;
+}
+
+export interface StepOutput {
+  index: number;
+  label: string;
+  output: string;
+  /** Set when the step passed its input through, e.g. text already in the target language */
+  note?: string | null;
+  /** Model that produced the output; unset when the step passed its input through */
+  model?: string | null;
+  duration_ms: number;
+}
+
+export interface StepFailure {
+  index: number;
+  label: string;
+  error: string;
+}
+
+export interface PipelineRun {
+  pipeline: string;
+  steps: StepOutput[];
+  /** Output of the last step, when every step succeeded */
+  output?: string | null;
+  failure?: StepFailure | null;
+  credential: string;
+  /** Model every step used; unset when the steps used different models */
+  model?: string | null;
+}
+
+export class PipelineService {
+  /** Runs the pipeline without a window; a failed step keeps the outputs of the steps before it. */
+  public async runPipeline(request: PipelineRequest): Promise<PipelineRun> {
+    return invoke<PipelineRun>('run_pipeline', { request });
+  }
+
+  /** Runs the pipeline in its own window, which shows each step's output as it streams. */
+  public async showPipelineWindow(request: PipelineRequest): Promise<void> {
+    return invoke('show_pipeline_window', { request });
+  }
+}
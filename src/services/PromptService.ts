@@ -0,0 +1,22 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type PromptKind = 'translate' | 'summarize';
+
+export interface PromptTemplate {
+  system: string;
+  user: string;
+}
+
+export interface PromptTemplates {
+  translate: PromptTemplate;
+  summarize: PromptTemplate;
+}
+
+export type PromptVariables = Partial<Record<'target_language' | 'text' | 'tone', string>>;
+
+export class PromptService {
+  /** Renders the stored template for `kind`, or `template` while it is being edited; rejects unknown variables. */
+  public async renderPrompt(kind: PromptKind, template?: PromptTemplate, variables?: PromptVariables): Promise<PromptTemplate> {
+    return invoke<PromptTemplate>('render_prompt', { kind, template, variables });
+  }
+}
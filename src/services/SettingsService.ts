@@ -0,0 +1,121 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { AudioProcessingConfig } from './AudioRecordingService.js';
+import type { PromptTemplates } from './PromptService.js';
+import type { Pipeline } from './PipelineService.js';
+import type { TranslationStyle } from './TranslationService.js';
+
+export interface RecordingLibraryConfig {
+  enabled: boolean;
+  format: 'wav_float32' | 'wav_pcm16' | 'wav_speech16k';
+  max_recordings?: number | null;
+  max_age_days?: number | null;
+}
+
+export type SummaryMode = 'standard' | 'tldr' | 'bullets' | 'executive' | 'action_items';
+
+export interface SummaryOptions {
+  mode: SummaryMode;
+  word_limit?: number | null;
+  output_language?: string | null;
+}
+
+export interface SmartTargetRule {
+  enabled: boolean;
+  alternate_language: string;
+}
+
+export interface TranslationMemoryConfig {
+  enabled: boolean;
+  fuzzy_threshold: number;
+  max_entries?: number | null;
+}
+
+export interface QualityCheckConfig {
+  enabled: boolean;
+  divergence_threshold: number;
+}
+
+export interface AlternativesConfig {
+  count: number;
+  /** Clipboard texts up to this length get alternatives from Cmd+T; 0 disables */
+  max_chars: number;
+}
+
+export interface Settings {
+  version: number;
+  target_language: string;
+  smart_target: SmartTargetRule;
+  extra_target_languages: string[];
+  translation_memory: TranslationMemoryConfig;
+  /** Last style chosen per target language, keyed by lowercase language name */
+  translation_styles: Record<string, TranslationStyle>;
+  quality_check: QualityCheckConfig;
+  alternatives: AlternativesConfig;
+  model: string;
+  temperature: number;
+  active_credential: string;
+  record_microphone: boolean;
+  silence_warning_seconds: number;
+  device_failover: boolean;
+  recordings: RecordingLibraryConfig;
+  audio_processing: AudioProcessingConfig;
+  prompts: PromptTemplates;
+  summary: SummaryOptions;
+  /** Saved pipelines, keyed by id */
+  pipelines: Record<string, Pipeline>;
+}
+
+export type SettingsPatch = Partial<Omit<Settings, 'recordings' | 'audio_processing' | 'prompts' | 'summary' | 'smart_target' | 'translation_memory' | 'quality_check' | 'alternatives' | 'translation_styles' | 'pipelines'>> & {
+  recordings?: Partial<RecordingLibraryConfig>;
+  audio_processing?: Partial<AudioProcessingConfig>;
+  prompts?: { [K in keyof PromptTemplates]?: Partial<PromptTemplates[K]> };
+  summary?: Partial<SummaryOptions>;
+  smart_target?: Partial<SmartTargetRule>;
+  translation_memory?: Partial<TranslationMemoryConfig>;
+  quality_check?: Partial<QualityCheckConfig>;
+  alternatives?: Partial<AlternativesConfig>;
+  /** `null` deletes the entry */
+  translation_styles?: Record<string, Partial<TranslationStyle> | null>;
+  /** `null` deletes the pipeline */
+  pipelines?: Record<string, Pipeline | null>;
+};
+
+// Preferences that lived in localStorage before the backend owned settings
+const LEGACY_KEYS: Record<string, (value: string) => SettingsPatch> = {
+  'selected-language': (value) => ({ target_language: value }),
+  'record-microphone': (value) => ({ record_microphone: value === 'true' }),
+};
+
+export class SettingsService {
+  public async getSettings(): Promise<Settings> {
+    return invoke<Settings>('get_settings');
+  }
+
+  /** Sends only the changed fields; the backend merges, validates and broadcasts `settings-changed`. */
+  public async updateSettings(patch: SettingsPatch): Promise<Settings> {
+    return invoke<Settings>('update_settings', { patch });
+  }
+
+  public async onSettingsChanged(callback: (settings: Settings) => void): Promise<UnlistenFn> {
+    return listen<Settings>('settings-changed', (event) => callback(event.payload));
+  }
+
+  public async migrateLocalStorage(): Promise<void> {
+    let patch: SettingsPatch = {};
+    for (const [key, toPatch] of Object.entries(LEGACY_KEYS)) {
+      const value = localStorage.getItem(key);
+      if (value !== null) {
+        patch = { ...patch, ...toPatch(value) };
+      }
+    }
+
+    if (Object.keys(patch).length === 0) {
+      return;
+    }
+
+    await this.updateSettings(patch);
+    Object.keys(LEGACY_KEYS).forEach((key) => localStorage.removeItem(key));
+    console.log('⚙️ Moved saved preferences from localStorage into settings');
+  }
+}
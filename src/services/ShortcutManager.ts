@@ -2,19 +2,47 @@ import { register, unregister } from '@tauri-apps/plugin-global-shortcut';
 import { AgentManager } from './AgentManager.js';
 import { readText } from '@tauri-apps/plugin-clipboard-manager';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { RecordingInterrupted, RecordingRecovered, SilenceWarning } from './AudioRecordingService.js';
+import { SettingsService } from './SettingsService.js';
+import { CustomAgent } from '../agents/custom/CustomAgent.js';
+import { PipelineAgent } from '../agents/pipeline/PipelineAgent.js';
 
 export class ShortcutManager {
   private agentManager: AgentManager;
   private registeredShortcuts: Set<string> = new Set();
   private lastShortcutTime: Map<string, number> = new Map();
   private readonly DEBOUNCE_MS = 1000; // Prevent rapid shortcuts within 1 second
+  private settingsService = new SettingsService();
 
   constructor(agentManager: AgentManager) {
     this.agentManager = agentManager;
+    this.listenForRecordingWarnings();
   }
 
-  private getSelectedLanguage(): string {
-    return localStorage.getItem('selected-language') || 'German';
+  private listenForRecordingWarnings(): void {
+    // Warn when a recording is running but nothing is arriving (e.g. muted monitor device)
+    listen<SilenceWarning>('audio-silence-warning', (event) => {
+      const { source, silent_seconds } = event.payload;
+      console.warn(`🔇 No ${source} audio for ${Math.round(silent_seconds)}s`);
+      this.showNotification(`🔇 No ${source} audio for ${Math.round(silent_seconds)}s. Is the device muted?`, 'error');
+    }).catch((error) => console.error('Failed to listen for silence warnings:', error));
+
+    listen<RecordingInterrupted>('recording-interrupted', (event) => {
+      const { source, device_name, will_recover } = event.payload;
+      const device = device_name ?? `${source} device`;
+      this.showNotification(`🔌 Lost ${device}${will_recover ? ', trying to reconnect...' : ''}`, 'error');
+    }).catch((error) => console.error('Failed to listen for recording interruptions:', error));
+
+    // Custom agents with the notification sink report their output here
+    listen<{ agent: string; name: string; message: string }>('custom-agent-notification', (event) => {
+      this.showNotification(`🤖 ${event.payload.name}: ${event.payload.message}`, 'success');
+    }).catch((error) => console.error('Failed to listen for custom agent notifications:', error));
+
+    listen<RecordingRecovered>('recording-recovered', (event) => {
+      const { source, device_name, gap_seconds } = event.payload;
+      this.showNotification(`✅ ${source} audio recovered on ${device_name ?? 'default device'} (${gap_seconds.toFixed(1)}s gap)`, 'success');
+    }).catch((error) => console.error('Failed to listen for recording recovery:', error));
   }
 
   public async registerAgentShortcuts(): Promise<void> {
@@ -73,6 +101,37 @@ export class ShortcutManager {
       
       this.lastShortcutTime.set(shortcut, now);
       
+      // Custom agents read their own input and deliver to their own sink in the backend
+      const agent = this.agentManager.getAgent(shortcut);
+      if (agent instanceof CustomAgent) {
+        const result = await this.agentManager.executeAgent(shortcut, {
+          input: '',
+          metadata: { source: 'custom_agent', agent: agent.definition.id }
+        });
+
+        if (!result.success) {
+          console.error(`${agent.getName()} failed:`, result.error);
+          this.showNotification(`Error: ${result.error}`, 'error');
+        } else if (agent.definition.output === 'clipboard') {
+          this.showNotification(`🤖 ${agent.getName()}: result copied to clipboard!`, 'success');
+        }
+        return;
+      }
+
+      // Pipelines read the clipboard or the last recording themselves and report in their own window
+      if (agent instanceof PipelineAgent) {
+        const result = await this.agentManager.executeAgent(shortcut, {
+          input: '',
+          metadata: { source: 'pipeline', pipeline: agent.id }
+        });
+
+        if (!result.success) {
+          console.error(`${agent.getName()} failed:`, result.error);
+          this.showNotification(`Error: ${result.error}`, 'error');
+        }
+        return;
+      }
+
       // For audio recording shortcut, handle separately (no clipboard needed)
       if (shortcut === 'cmd+r') {
         try {
@@ -103,6 +162,20 @@ export class ShortcutManager {
         return;
       }
 
+      // For meeting notes shortcut, work from the last recording (no clipboard needed)
+      if (shortcut === 'cmd+m') {
+        const result = await this.agentManager.executeAgent(shortcut, {
+          input: 'meeting_notes',
+          metadata: { source: 'last_recording' }
+        });
+
+        if (!result.success) {
+          console.error('Meeting notes failed:', result.error);
+          this.showNotification(`Error: ${result.error}`, 'error');
+        }
+        return;
+      }
+
       // For other shortcuts, get clipboard content
       const clipboardText = await readText();
       
@@ -117,13 +190,20 @@ export class ShortcutManager {
       // For translation shortcut, open the translation window
       if (shortcut === 'cmd+t') {
         try {
-          const selectedLanguage = this.getSelectedLanguage();
-          console.log(`🌍 Using selected language for translation: ${selectedLanguage}`);
+          const settings = await this.settingsService.getSettings();
+          const selectedLanguage = settings.target_language;
+          const targetLanguages = [selectedLanguage, ...settings.extra_target_languages];
+          console.log(`🌍 Using selected language(s) for translation: ${targetLanguages.join(', ')}`);
+          // Short texts such as UI strings get several candidates to choose from
+          const { count, max_chars } = settings.alternatives;
+          const alternatives = clipboardText.trim().length <= max_chars ? count : null;
           
-          // Open translation window (the window will handle the translation)
+          // Open translation window (the window translates into every language concurrently, one tab each)
           await invoke('show_translation_window', {
             text: clipboardText,
-            targetLanguage: selectedLanguage
+            targetLanguage: selectedLanguage,
+            targetLanguages,
+            alternatives
           });
         } catch (error) {
           console.error('Failed to open translation window:', error);
@@ -148,6 +228,34 @@ export class ShortcutManager {
         return;
       }
 
+      // For the rewrite shortcut, open the rewrite window (it starts with a grammar fix)
+      if (shortcut === 'cmd+g') {
+        try {
+          console.log(`✍️ Opening rewrite window for text (${clipboardText.length} chars)...`);
+          await invoke('show_rewrite_window', {
+            text: clipboardText
+          });
+        } catch (error) {
+          console.error('Failed to open rewrite window:', error);
+          this.showNotification('Failed to open rewrite window', 'error');
+        }
+        return;
+      }
+
+      // For the code shortcut, open the code explainer window (it starts with an explanation)
+      if (shortcut === 'cmd+e') {
+        try {
+          console.log(`🧑‍💻 Opening code explainer window for text (${clipboardText.length} chars)...`);
+          await invoke('show_code_window', {
+            text: clipboardText
+          });
+        } catch (error) {
+          console.error('Failed to open code explainer window:', error);
+          this.showNotification('Failed to open code explainer window', 'error');
+        }
+        return;
+      }
+
       // For other shortcuts, use the original agent system
       const result = await this.agentManager.executeAgent(shortcut, {
         input: clipboardText,
@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface MemoryEntry {
+  id: number;
+  source_text: string;
+  translation: string;
+  target_language: string;
+  model: string;
+  glossary_version: string;
+  created_at: string;
+  last_used_at: string;
+  hits: number;
+}
+
+export interface PurgeFilter {
+  ids?: number[];
+  targetLanguage?: string;
+  olderThanDays?: number;
+}
+
+export class TranslationMemoryService {
+  /** Most recently used first; `query` matches source or translation text. */
+  public async listEntries(query?: string, targetLanguage?: string, limit = 100, offset = 0): Promise<MemoryEntry[]> {
+    return invoke<MemoryEntry[]>('list_translation_memory', { query, targetLanguage, limit, offset });
+  }
+
+  /** Without a filter the whole memory is cleared. Resolves to the number of deleted entries. */
+  public async purge(filter: PurgeFilter = {}): Promise<number> {
+    return invoke<number>('purge_translation_memory', { ...filter });
+  }
+}
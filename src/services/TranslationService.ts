@@ -3,21 +3,77 @@ import { invoke } from '@tauri-apps/api/core';
 export interface TranslationRequest {
   text: string;
   target_language: string;
-  api_key: string;
+  // Name of a stored credential; the key itself is resolved by the backend
+  credential?: string;
+  /** Translates text nodes only and keeps markup intact; detected when omitted */
+  format?: 'plain' | 'markdown' | 'html' | 'code';
+  /** Unset style fields use what was last chosen for the target language */
+  formality?: Formality;
+  tone?: Tone;
+  /** Regional variant, e.g. "pt-BR" */
+  locale?: string;
+  /** Back-translate the result and flag divergent sentences; defaults to the quality_check setting */
+  verify?: boolean;
+}
+
+export type Formality = 'formal' | 'informal';
+
+export type Tone = 'neutral' | 'marketing' | 'technical' | 'casual';
+
+export interface TranslationStyle {
+  formality?: Formality | null;
+  tone?: Tone | null;
+  locale?: string | null;
+}
+
+export interface ResponseMetadata {
+  credential: string;
+  model: string;
+}
+
+export interface GlossaryViolation {
+  term: string;
+  expected: string;
+}
+
+export interface DetectedLanguage {
+  name: string;
+  code: string;
+  confidence: number;
+  reliable: boolean;
+}
+
+export interface SentenceDivergence {
+  source: string;
+  back_translation: string;
+  similarity: number;
+}
+
+export interface QualityReport {
+  source_language: string;
+  back_translation: string;
+  similarity: number;
+  divergent_sentences: SentenceDivergence[];
 }
 
 export interface TranslationResponse {
   success: boolean;
   translated_text?: string;
   error?: string;
+  metadata?: ResponseMetadata;
+  glossary_violations: GlossaryViolation[];
+  source_language?: DetectedLanguage | null;
+  target_language: string;
+  memory_score?: number | null;
+  style: TranslationStyle;
+  quality?: QualityReport | null;
 }
 
 export class TranslationService {
-  private apiKey: string;
   private defaultTargetLanguage: string;
+  private credential?: string;
 
-  constructor(apiKey: string, defaultTargetLanguage: string = 'English') {
-    this.apiKey = apiKey;
+  constructor(defaultTargetLanguage: string = 'English') {
     this.defaultTargetLanguage = defaultTargetLanguage;
   }
 
@@ -25,7 +81,7 @@ export class TranslationService {
     const request: TranslationRequest = {
       text,
       target_language: targetLanguage || this.defaultTargetLanguage,
-      api_key: this.apiKey
+      credential: this.credential
     };
 
     try {
@@ -39,8 +95,8 @@ export class TranslationService {
     }
   }
 
-  public setApiKey(apiKey: string): void {
-    this.apiKey = apiKey;
+  public setCredential(credential?: string): void {
+    this.credential = credential;
   }
 
   public setDefaultTargetLanguage(language: string): void {